@@ -0,0 +1,228 @@
+//! Bandwidth limits that change with the time of day.
+//!
+//! Rclone's `core/bwlimit` only takes a single rate, so the timetable is kept
+//! here and the rate for the current time gets passed on to Rclone as syncing
+//! goes on.
+//!
+//! A schedule uses the same format as Rclone's `--bwlimit` option: either a
+//! single rate such as `1M`, or a list of times and the rate to use from then
+//! on, such as `09:00,1M 18:00,off`. A rate can also be split into an upload
+//! and download rate, such as `1M:10M`.
+use adw::glib;
+
+/// The rate Rclone uses for no limit at all.
+pub static UNLIMITED: &str = "off";
+
+/// A parsed bandwidth limit schedule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BandwidthSchedule {
+    /// The minute of the day each rate starts at, sorted by time.
+    entries: Vec<(u32, String)>,
+}
+
+impl BandwidthSchedule {
+    /// Parse a schedule, returning an error message if it's invalid. An empty
+    /// schedule means no limit.
+    pub fn parse(schedule: &str) -> Result<Self, String> {
+        let schedule = schedule.trim();
+
+        if schedule.is_empty() {
+            return Ok(Self {
+                entries: vec![(0, UNLIMITED.to_owned())],
+            });
+        }
+
+        // A single rate that applies all day.
+        if !schedule.contains(',') {
+            let rate = parse_rate(schedule)?;
+            return Ok(Self {
+                entries: vec![(0, rate)],
+            });
+        }
+
+        let mut entries = vec![];
+
+        for entry in schedule.split_whitespace() {
+            let Some((time, rate)) = entry.split_once(',') else {
+                return Err(tr::tr!(
+                    "'{}' should be a time and a rate, like '09:00,1M'.",
+                    entry
+                ));
+            };
+            entries.push((parse_time(time)?, parse_rate(rate)?));
+        }
+
+        entries.sort_by_key(|(minute, _)| *minute);
+        Ok(Self { entries })
+    }
+
+    /// Get the rate that applies at the given minute of the day.
+    pub fn rate_at(&self, minute: u32) -> &str {
+        // Before the first entry of the day, the last one from the day before still
+        // applies.
+        self.entries
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= minute)
+            .or_else(|| self.entries.last())
+            .map(|(_, rate)| rate.as_str())
+            .unwrap_or(UNLIMITED)
+    }
+
+    /// Get the rate that applies right now, in local time.
+    pub fn current_rate(&self) -> &str {
+        let now = glib::DateTime::now_local().unwrap();
+        self.rate_at((now.hour() * 60 + now.minute()) as u32)
+    }
+}
+
+/// Combine several rates into the strictest one, as Rclone only has a single
+/// limit for all transfers. The upload and download parts of the rates are
+/// combined on their own.
+pub fn strictest_rate<'a>(rates: impl IntoIterator<Item = &'a str>) -> String {
+    // The strictest upload and download rate so far, along with their size in
+    // bytes.
+    let mut upload: Option<(&str, f64)> = None;
+    let mut download: Option<(&str, f64)> = None;
+
+    for rate in rates {
+        let (upload_rate, download_rate) = rate.split_once(':').unwrap_or((rate, rate));
+
+        for (strictest, rate) in [(&mut upload, upload_rate), (&mut download, download_rate)] {
+            if let Some(bytes) = rate_bytes(rate)
+                && strictest.map_or(true, |(_, strictest_bytes)| bytes < strictest_bytes)
+            {
+                *strictest = Some((rate, bytes));
+            }
+        }
+    }
+
+    let upload = upload.map_or(UNLIMITED, |(rate, _)| rate);
+    let download = download.map_or(UNLIMITED, |(rate, _)| rate);
+
+    if upload == download {
+        upload.to_owned()
+    } else {
+        format!("{upload}:{download}")
+    }
+}
+
+/// Get the number of bytes per second of a single rate as returned by
+/// [`parse_rate`], or [`None`] if it's unlimited. Like in Rclone, a rate
+/// without a suffix is in KiB.
+fn rate_bytes(rate: &str) -> Option<f64> {
+    if rate.eq_ignore_ascii_case(UNLIMITED) {
+        return None;
+    }
+
+    let number = rate.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let multiplier = match rate[number.len()..].to_ascii_lowercase().as_str() {
+        "b" => 1.0,
+        "" | "k" => 1024.0,
+        "m" => 1024.0_f64.powi(2),
+        "g" => 1024.0_f64.powi(3),
+        "t" => 1024.0_f64.powi(4),
+        "p" => 1024.0_f64.powi(5),
+        _ => return None,
+    };
+
+    number.parse::<f64>().ok().map(|number| number * multiplier)
+}
+
+/// Parse a time in the form of `HH:MM` into the minute of the day.
+fn parse_time(time: &str) -> Result<u32, String> {
+    let invalid = || tr::tr!("'{}' isn't a valid time, like '09:00'.", time);
+    let (hour, minute) = time.split_once(':').ok_or_else(invalid)?;
+    let hour: u32 = hour.parse().map_err(|_| invalid())?;
+    let minute: u32 = minute.parse().map_err(|_| invalid())?;
+
+    if hour > 23 || minute > 59 {
+        return Err(invalid());
+    }
+
+    Ok(hour * 60 + minute)
+}
+
+/// Check that a rate is one Rclone understands, such as `off`, `512k`, or
+/// `1M:10M`, returning it in the form to pass on to Rclone.
+fn parse_rate(rate: &str) -> Result<String, String> {
+    let parse_size = |size: &str| {
+        if size.eq_ignore_ascii_case(UNLIMITED) {
+            return Some(UNLIMITED.to_owned());
+        }
+
+        let number = size.trim_end_matches(|c: char| "bkmgtpBKMGTP".contains(c));
+        let valid = size.len() - number.len() <= 1
+            && number.parse::<f64>().is_ok_and(|number| number >= 0.0);
+        valid.then(|| size.to_owned())
+    };
+    let parsed = match rate.split_once(':') {
+        Some((upload, download)) => parse_size(upload)
+            .zip(parse_size(download))
+            .map(|(upload, download)| format!("{upload}:{download}")),
+        None => parse_size(rate),
+    };
+
+    parsed.ok_or_else(|| tr::tr!("'{}' isn't a valid rate, like '1M' or 'off'.", rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_schedule_is_unlimited() {
+        let schedule = BandwidthSchedule::parse("  ").unwrap();
+        assert_eq!(schedule.rate_at(0), UNLIMITED);
+        assert_eq!(schedule.rate_at(12 * 60), UNLIMITED);
+    }
+
+    #[test]
+    fn single_rate_applies_all_day() {
+        let schedule = BandwidthSchedule::parse("1M").unwrap();
+        assert_eq!(schedule.rate_at(0), "1M");
+        assert_eq!(schedule.rate_at(23 * 60 + 59), "1M");
+    }
+
+    #[test]
+    fn timetable() {
+        let schedule = BandwidthSchedule::parse("18:00,off 09:00,512k:10M").unwrap();
+        assert_eq!(schedule.rate_at(9 * 60), "512k:10M");
+        assert_eq!(schedule.rate_at(17 * 60 + 59), "512k:10M");
+        assert_eq!(schedule.rate_at(18 * 60), UNLIMITED);
+
+        // The last entry of the day carries over past midnight.
+        assert_eq!(schedule.rate_at(8 * 60), UNLIMITED);
+    }
+
+    #[test]
+    fn off_is_case_insensitive() {
+        assert!(BandwidthSchedule::parse("OFF").is_ok());
+    }
+
+    #[test]
+    fn strictest_rate_per_direction() {
+        assert_eq!(strictest_rate([UNLIMITED, UNLIMITED]), UNLIMITED);
+        assert_eq!(strictest_rate(["1M", UNLIMITED]), "1M");
+        assert_eq!(strictest_rate(["1M", "512k"]), "512k");
+        assert_eq!(strictest_rate(["2048", "1M"]), "1M");
+        assert_eq!(strictest_rate(["1M:10M", "5M"]), "1M:5M");
+        assert_eq!(strictest_rate(["off:10M", "5M:off"]), "5M:10M");
+    }
+
+    #[test]
+    fn invalid_schedules() {
+        for schedule in [
+            "fast",
+            "1MM",
+            "-1M",
+            "1M:",
+            "09:00,1M 25:00,off",
+            "09:60,1M",
+            "9am,1M",
+            "09:00,fast",
+        ] {
+            assert!(BandwidthSchedule::parse(schedule).is_err(), "{schedule}");
+        }
+    }
+}
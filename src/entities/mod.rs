@@ -1,19 +1,47 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.3
 mod remotes;
+mod sea_orm_active_enums;
+mod settings;
 mod sync_dirs;
+mod sync_events;
 mod sync_items;
+mod sync_retries;
+mod sync_skipped_dirs;
 
 pub use remotes::ActiveModel as RemotesActiveModel;
 pub use remotes::Column as RemotesColumn;
 pub use remotes::Entity as RemotesEntity;
 pub use remotes::Model as RemotesModel;
 
+pub use sea_orm_active_enums::ConflictPolicy;
+pub use sea_orm_active_enums::SyncAction;
+pub use sea_orm_active_enums::SyncDirection;
+pub use sea_orm_active_enums::SyncResult;
+pub use sea_orm_active_enums::VersionStorage;
+
+pub use settings::ActiveModel as SettingsActiveModel;
+pub use settings::Entity as SettingsEntity;
+pub use settings::Model as SettingsModel;
+
 pub use sync_dirs::ActiveModel as SyncDirsActiveModel;
 pub use sync_dirs::Column as SyncDirsColumn;
 pub use sync_dirs::Entity as SyncDirsEntity;
 pub use sync_dirs::Model as SyncDirsModel;
 
+pub use sync_events::ActiveModel as SyncEventsActiveModel;
+pub use sync_events::Column as SyncEventsColumn;
+pub use sync_events::Entity as SyncEventsEntity;
+pub use sync_events::Model as SyncEventsModel;
+
 pub use sync_items::ActiveModel as SyncItemsActiveModel;
 pub use sync_items::Column as SyncItemsColumn;
 pub use sync_items::Entity as SyncItemsEntity;
 pub use sync_items::Model as SyncItemsModel;
+
+pub use sync_retries::ActiveModel as SyncRetriesActiveModel;
+pub use sync_retries::Column as SyncRetriesColumn;
+pub use sync_retries::Entity as SyncRetriesEntity;
+
+pub use sync_skipped_dirs::ActiveModel as SyncSkippedDirsActiveModel;
+pub use sync_skipped_dirs::Column as SyncSkippedDirsColumn;
+pub use sync_skipped_dirs::Entity as SyncSkippedDirsEntity;
@@ -1,4 +1,5 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.3
+use crate::util;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +9,23 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub name: String,
+    /// The number of files to transfer at once.
+    pub max_transfers: i32,
+    /// The bandwidth limit for this remote, overriding the global one in
+    /// [`super::settings::Model::bandwidth_limit`].
+    pub bandwidth_limit: Option<String>,
+    /// The number of seconds between checks for changes, before any backoff.
+    pub poll_interval: i32,
+}
+
+impl Model {
+    // See if this item still exists in the database (i.e. the struct was created
+    // and the item was later deleted).
+    pub fn exists(&self, db: &DatabaseConnection) -> bool {
+        util::await_future(Entity::find_by_id(self.id).one(db))
+            .unwrap()
+            .is_some()
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
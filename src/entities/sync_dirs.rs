@@ -1,4 +1,5 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.3
+use super::sea_orm_active_enums::{ConflictPolicy, SyncDirection, VersionStorage};
 use crate::util;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -15,6 +16,28 @@ pub struct Model {
     /// The remote path being synced, as an absolute path (though it won't start
     /// with `/`).
     pub remote_path: String,
+    /// What to do when an item changed on both sides since the last sync.
+    pub conflict_policy: ConflictPolicy,
+    /// Which way changes are synced.
+    pub sync_direction: SyncDirection,
+    /// The path on the remote that deleted remote items get moved to, relative
+    /// to the root of the remote.
+    pub remote_trash_path: String,
+    /// The percentage of synced items a sync can delete before asking the user
+    /// for confirmation.
+    pub mass_deletion_percent: i32,
+    /// The number of items a sync can delete before asking the user for
+    /// confirmation.
+    pub mass_deletion_count: i32,
+    /// Whether syncing is paused for this directory.
+    pub paused: bool,
+    /// Where to keep the previous versions of overwritten files.
+    pub version_storage: VersionStorage,
+    /// The number of previous versions to keep for each file, or 0 for no
+    /// limit.
+    pub max_versions: i32,
+    /// The number of days to keep previous versions for, or 0 for no limit.
+    pub max_version_days: i32,
 }
 
 impl Model {
@@ -42,8 +65,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Remotes,
+    #[sea_orm(has_many = "super::sync_events::Entity")]
+    SyncEvents,
     #[sea_orm(has_many = "super::sync_items::Entity")]
     SyncItems,
+    #[sea_orm(has_many = "super::sync_retries::Entity")]
+    SyncRetries,
+    #[sea_orm(has_many = "super::sync_skipped_dirs::Entity")]
+    SyncSkippedDirs,
 }
 
 impl Related<super::remotes::Entity> for Entity {
@@ -52,10 +81,28 @@ impl Related<super::remotes::Entity> for Entity {
     }
 }
 
+impl Related<super::sync_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SyncEvents.def()
+    }
+}
+
 impl Related<super::sync_items::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SyncItems.def()
     }
 }
 
+impl Related<super::sync_retries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SyncRetries.def()
+    }
+}
+
+impl Related<super::sync_skipped_dirs::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SyncSkippedDirs.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -17,6 +17,16 @@ pub struct Model {
     pub last_local_timestamp: i32,
     /// The remote UNIX timestamp of the item when last synced.
     pub last_remote_timestamp: i32,
+    /// The size of the item in bytes when last synced, if it's a file.
+    pub last_size: Option<i64>,
+    /// The hash type of `Self::last_hash`, such as `md5` or `sha1`.
+    pub hash_type: Option<String>,
+    /// The hash of the item's content when last synced, if it's a file and the
+    /// remote supports hashes.
+    pub last_hash: Option<String>,
+    /// The inode number of the local item when last synced, used to find the
+    /// item again after it gets moved or renamed.
+    pub local_inode: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
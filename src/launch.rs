@@ -1,48 +1,54 @@
 use crate::{
+    bwlimit::{self, BandwidthSchedule},
     entities::{
-        RemotesColumn, RemotesEntity, RemotesModel, SyncDirsActiveModel, SyncDirsColumn,
-        SyncDirsEntity, SyncDirsModel, SyncItemsActiveModel, SyncItemsColumn, SyncItemsEntity,
+        ConflictPolicy, RemotesActiveModel, RemotesColumn, RemotesEntity, RemotesModel,
+        SettingsActiveModel, SettingsModel, SyncAction, SyncDirection, SyncDirsActiveModel,
+        SyncDirsColumn, SyncDirsEntity, SyncDirsModel, SyncEventsColumn, SyncEventsEntity,
+        SyncEventsModel, SyncResult, VersionStorage,
     },
     gtk_util,
     login::{self},
     migrations::{Migrator, MigratorTrait},
-    rclone::{self, RcloneListFilter},
+    preferences,
+    rclone::{self, RcloneAbout, RcloneErrorKind, RcloneListFilter},
+    sync::{
+        self, ConflictResolution, FileProgress, LocalChanges, LocalWatcher, PlanAction,
+        PollScheduler, SyncEngine, SyncError, SyncEvent, SyncPlan, SyncRoot, TransferKind,
+        TransferProgress, Versions, FILE_IGNORE_NAME, VERSIONS_DIR_NAME,
+    },
     traits::prelude::*,
     util,
 };
 use adw::{
-    glib,
+    gio, glib,
     gtk::{
-        pango::EllipsizeMode, Align, Box, Button, ButtonsType, Entry, EntryCompletion,
-        FileChooserDialog, FileFilter, GestureClick, Image, Inhibit, Label, ListBox, ListBoxRow,
-        ListStore, MessageDialog, Orientation, PolicyType, Popover, PositionType, ResponseType,
-        ScrolledWindow, SelectionMode, Separator, Spinner, Stack, StackSidebar,
-        StackTransitionType, Widget,
+        pango::EllipsizeMode, Align, Box, Button, ButtonsType, CheckButton, DropDown, Entry,
+        EntryCompletion, FileChooserDialog, FileFilter, GestureClick, Image, Inhibit, Label,
+        LevelBar, ListBox, ListBoxRow, ListStore, MessageDialog, Orientation, PolicyType, Popover,
+        PositionType, ResponseType, ScrolledWindow, SearchEntry, SelectionMode, Separator,
+        SpinButton, Spinner, Stack, StackSidebar, StackTransitionType, StringList, Widget,
     },
     prelude::*,
-    Application, ApplicationWindow, Bin, EntryRow, HeaderBar, Leaflet, LeafletTransitionType,
-    WindowTitle,
+    ActionRow, Application, ApplicationWindow, Bin, ComboRow, EntryRow, HeaderBar, Leaflet,
+    LeafletTransitionType, WindowTitle,
 };
 use file_lock::{FileLock, FileOptions};
 use indexmap::IndexMap;
-use sea_orm::{entity::prelude::*, ActiveValue, Database, DatabaseConnection};
+use sea_orm::{entity::prelude::*, ActiveValue, Database, QueryOrder, QuerySelect};
 
 use std::{
     boxed,
-    cell::RefCell,
-    collections::HashMap,
-    fs::{self, File, OpenOptions},
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    fs::{self, OpenOptions},
     io::Write,
     path::{Path, PathBuf},
     rc::Rc,
     sync::{Arc, Mutex},
     thread,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant},
 };
 
-// The location for file ignore lists.
-static FILE_IGNORE_NAME: &str = ".sync-exclude.lst";
-
 // A [`HashMap`] containing the status and progress for a directory sync label.
 // This is done here because if we try to get the child from a `Box` or
 // something we just get a generic gtk `Widget`, which we can't use.
@@ -56,16 +62,12 @@ type RemoteDeletionQueue = Rc<RefCell<Vec<String>>>;
 // occurring.
 type SyncDirDeletionQueue = Rc<RefCell<Vec<(String, String, String)>>>;
 
-/// The errors that can be found while syncing.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
-enum SyncError {
-    /// A general catch-all error. A tuple of the path the error happened at,
-    /// and the error message itself.
-    General(String, String),
-    /// An error when both the local and remote file are more current than at
-    /// the last sync. A tuple of the local and remote file.
-    BothMoreCurrent(String, String),
-}
+// A [`HashMap`] containing the storage usage display for each remote's page,
+// keyed by the remote's name.
+type QuotaMap = Rc<RefCell<HashMap<String, QuotaDisplay>>>;
+
+/// How often to check the storage usage of each remote.
+const QUOTA_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
 
 impl SyncError {
     fn generate_ui(&self) -> Box {
@@ -79,29 +81,38 @@ impl SyncError {
             .build();
 
         match self {
-            SyncError::General(file_path, err) => {
-                let err_label = Label::builder()
+            SyncError::General(file_path, kind, err) => {
+                let file_label = Label::builder()
                     .label(file_path)
                     .halign(Align::Start)
                     .ellipsize(EllipsizeMode::End)
                     .build();
-                let file_label = Label::builder()
-                    .label(err)
+                let err_label = Label::builder()
+                    .label(&kind.message())
+                    .tooltip_text(err)
                     .halign(Align::Start)
                     .ellipsize(EllipsizeMode::End)
                     .css_classes(vec!["caption".to_string(), "dim-label".to_string()])
                     .build();
-                error_container.append(&err_label);
-                error_container.append(&file_label);
+                let label_container = Box::builder()
+                    .orientation(Orientation::Vertical)
+                    .spacing(2)
+                    .hexpand(true)
+                    .build();
+                label_container.append(&file_label);
+                label_container.append(&err_label);
+
+                let item_container = Box::builder()
+                    .orientation(Orientation::Horizontal)
+                    .spacing(10)
+                    .build();
+                item_container.append(&get_image(kind.icon_name()));
+                item_container.append(&label_container);
+                error_container.append(&item_container);
             }
-            SyncError::BothMoreCurrent(local_path, remote_path) => {
-                let err_msg = tr::tr!(
-                    "Both '{}' and '{}' are more recent than at last sync.",
-                    local_path,
-                    remote_path
-                );
+            SyncError::BothMoreCurrent(_, _) | SyncError::MassDeletion(_, _) => {
                 let err_label = Label::builder()
-                    .label(&err_msg)
+                    .label(&self.message())
                     .halign(Align::Start)
                     .ellipsize(EllipsizeMode::End)
                     .build();
@@ -112,6 +123,24 @@ impl SyncError {
         error_container
     }
 }
+
+impl RcloneErrorKind {
+    /// Get the name of the icon to show for this kind of error.
+    fn icon_name(&self) -> &'static str {
+        match self {
+            Self::Network => "network-error-symbolic",
+            Self::AuthExpired | Self::TwoFactorRequired => "dialog-password-symbolic",
+            Self::PermissionDenied => "action-unavailable-symbolic",
+            Self::QuotaExceeded => "drive-harddisk-symbolic",
+            Self::InvalidName => "document-edit-symbolic",
+            Self::NotFound => "edit-find-symbolic",
+            Self::RateLimited => "preferences-system-time-symbolic",
+            Self::LocalIo => "computer-symbolic",
+            Self::Cancelled => "process-stop-symbolic",
+            Self::Other => "dialog-warning-symbolic",
+        }
+    }
+}
 /// A struct representing all the data that belongs to a sync directory.
 struct SyncDir {
     /// The parent stack for [`Self::container`], this contains all the UI
@@ -132,6 +161,104 @@ struct SyncDir {
     error_items: HashMap<SyncError, Box>,
     /// A closure to update the UI error listing.
     update_error_ui: boxed::Box<dyn Fn()>,
+    /// The container for the running transfers in the more info page, hidden
+    /// while there aren't any.
+    transfer_container: Box,
+    /// The list of running transfers.
+    transfer_list: ListBox,
+    /// The row and progress label of each running transfer in
+    /// [`Self::transfer_list`], keyed by the ID of the Rclone job running it.
+    transfer_rows: HashMap<u64, (ListBoxRow, Label)>,
+}
+
+impl SyncDir {
+    /// Show the progress of the running transfers, each with a button to
+    /// cancel it.
+    fn update_transfers(&mut self, files: &[FileProgress]) {
+        self.transfer_rows.retain(|job_id, (row, _)| {
+            let running = files.iter().any(|file| file.job_id == *job_id);
+
+            if !running {
+                self.transfer_list.remove(row);
+            }
+
+            running
+        });
+
+        for file in files {
+            let (_, label) = self.transfer_rows.entry(file.job_id).or_insert_with(|| {
+                let sections = Box::builder()
+                    .orientation(Orientation::Horizontal)
+                    .spacing(10)
+                    .margin_top(6)
+                    .margin_end(6)
+                    .margin_bottom(6)
+                    .margin_start(6)
+                    .build();
+                let label = Label::builder()
+                    .halign(Align::Start)
+                    .hexpand(true)
+                    .ellipsize(EllipsizeMode::Middle)
+                    .build();
+                let cancel_button = Button::builder()
+                    .icon_name("process-stop-symbolic")
+                    .tooltip_text(&tr::tr!("Cancel this transfer"))
+                    .valign(Align::Center)
+                    .css_classes(vec!["flat".to_string()])
+                    .build();
+                let job_id = file.job_id;
+                cancel_button.connect_clicked(move |button| {
+                    button.set_sensitive(false);
+
+                    // The transfer may have just finished, in which case there's nothing left
+                    // to cancel.
+                    let _ = rclone::sync::stop_job(job_id);
+                });
+                sections.append(&label);
+                sections.append(&cancel_button);
+
+                let row = ListBoxRow::builder().child(&sections).build();
+                self.transfer_list.append(&row);
+                (row, label)
+            });
+            label.set_label(&file_progress_label(file));
+        }
+
+        self.transfer_container.set_visible(!files.is_empty());
+    }
+}
+
+/// The widgets showing the storage usage of a remote in its page header.
+struct QuotaDisplay {
+    /// The container for the below widgets, hidden until the usage is known.
+    container: Box,
+    /// The label with a summary of the used and free space.
+    label: Label,
+    /// The bar showing how full the remote is.
+    level: LevelBar,
+}
+
+impl QuotaDisplay {
+    /// Show the storage usage reported by the remote, or hide the display if
+    /// it doesn't report any.
+    fn update(&self, about: Option<&RcloneAbout>) {
+        let Some(summary) = about.and_then(|about| about.summary()) else {
+            self.container.set_visible(false);
+            return;
+        };
+
+        self.label.set_label(&summary);
+
+        match about.and_then(|about| about.usage()) {
+            Some(usage) => {
+                self.level.set_value(usage.min(1.0));
+                self.level.set_visible(true);
+            }
+            None => self.level.set_visible(false),
+        }
+
+        self.container.set_visible(true);
+    }
 }
 
 lazy_static::lazy_static! {
@@ -139,6 +266,10 @@ lazy_static::lazy_static! {
     pub static ref CLOSE_REQUEST: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
     // A [`Mutex`] to keep track of open requests from the tray icon.
     pub static ref OPEN_REQUEST: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+    // A [`Mutex`] to keep track of requests from the tray icon to check all remotes for changes right away.
+    pub static ref CHECK_REQUEST: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+    // A [`Mutex`] to keep track of requests from the tray icon to pause (`true`) or resume (`false`) syncing.
+    pub static ref PAUSE_REQUEST: Arc<Mutex<Option<bool>>> = Arc::new(Mutex::new(None));
 }
 
 /// Get an icon for use as the status icon for directory syncs.
@@ -150,6 +281,831 @@ fn get_image(icon_name: &str) -> Image {
         .build()
 }
 
+/// Show that a root of a sync directory is missing in its status.
+fn show_root_missing(item: &SyncDir, root: SyncRoot, sync_dir: &SyncDirsModel) {
+    let status_string = match root {
+        SyncRoot::Local => tr::tr!(
+            "Sync root missing: '{}' couldn't be found. Syncing will resume once it's back.",
+            util::fmt_home(&sync_dir.local_path)
+        ),
+        SyncRoot::Remote => tr::tr!(
+            "Sync root missing: '{}' couldn't be found on the remote. Syncing will resume once it's back.",
+            sync_dir.remote_path
+        ),
+    };
+    item.status_text.set_label(&status_string);
+    item.status_icon
+        .set_child(Some(&get_image("drive-removable-media-symbolic")));
+}
+
+/// Get the label to show for a conflict policy in the UI.
+fn conflict_policy_label(policy: ConflictPolicy) -> String {
+    match policy {
+        ConflictPolicy::Ask => tr::tr!("Ask"),
+        ConflictPolicy::PreferLocal => tr::tr!("Prefer Local"),
+        ConflictPolicy::PreferRemote => tr::tr!("Prefer Remote"),
+        ConflictPolicy::NewestWins => tr::tr!("Newest Wins"),
+        ConflictPolicy::KeepBoth => tr::tr!("Keep Both"),
+    }
+}
+
+/// Get the label to show for a sync direction in the UI.
+fn sync_direction_label(direction: SyncDirection) -> String {
+    match direction {
+        SyncDirection::TwoWay => tr::tr!("Two-way"),
+        SyncDirection::UploadOnly => tr::tr!("Upload only"),
+        SyncDirection::DownloadOnly => tr::tr!("Download only"),
+        SyncDirection::Mirror => tr::tr!("Mirror local folder to remote"),
+    }
+}
+
+/// Format a number of seconds as a duration, such as `1:05:09` or `5:09`.
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Get a description of how far along a running transfer is, such as
+/// `Uploading 'video.mkv': 1.2 GB of 4.0 GB, 5.0 MB/s, 9:20 left`.
+fn file_progress_label(file: &FileProgress) -> String {
+    let name = Path::new(&file.local_path)
+        .file_name()
+        .unwrap()
+        .to_string_lossy();
+    let bytes = glib::format_size(file.bytes);
+    let size = glib::format_size(file.size);
+    let speed = glib::format_size(file.speed as u64);
+    let label = match file.kind {
+        TransferKind::Upload => tr::tr!("Uploading '{}': {} of {}, {}/s", name, bytes, size, speed),
+        TransferKind::Download => {
+            tr::tr!("Downloading '{}': {} of {}, {}/s", name, bytes, size, speed)
+        }
+    };
+
+    match file.eta {
+        Some(eta) => tr::tr!("{}, {} left", label, format_duration(eta)),
+        None => label,
+    }
+}
+
+/// Get a summary of the transfers started during a sync, such as `3 of 10
+/// files, 1.2 GB of 4.0 GB transferred`.
+fn transfer_progress_label(progress: &TransferProgress) -> String {
+    tr::tr!(
+        "{} of {} files, {} of {} transferred",
+        progress.finished,
+        progress.total,
+        glib::format_size(progress.bytes),
+        glib::format_size(progress.total_bytes)
+    )
+}
+
+impl PlanAction {
+    /// Get the label to show for this action in a sync plan.
+    fn label(&self) -> String {
+        match self {
+            Self::Upload => tr::tr!("Upload"),
+            Self::Download => tr::tr!("Download"),
+            Self::DeleteLocal => tr::tr!("Delete locally"),
+            Self::DeleteRemote => tr::tr!("Delete on remote"),
+            Self::Move => tr::tr!("Move on remote"),
+            Self::Conflict(None) => tr::tr!("Conflict, you'll be asked which side to keep"),
+            Self::Conflict(Some(ConflictResolution::Local)) => tr::tr!("Conflict, keep local"),
+            Self::Conflict(Some(ConflictResolution::Remote)) => tr::tr!("Conflict, keep remote"),
+            Self::Conflict(Some(ConflictResolution::KeepBoth)) => tr::tr!("Conflict, keep both"),
+            Self::TypeChange(SyncRoot::Local) => tr::tr!("Replace local item of another type"),
+            Self::TypeChange(SyncRoot::Remote) => tr::tr!("Replace remote item of another type"),
+        }
+    }
+
+    /// Get the name of the icon to show for this action in a sync plan.
+    fn icon_name(&self) -> &'static str {
+        match self {
+            Self::Upload => "go-up-symbolic",
+            Self::Download => "go-down-symbolic",
+            Self::DeleteLocal | Self::DeleteRemote => "user-trash-symbolic",
+            Self::Move => "document-send-symbolic",
+            Self::Conflict(_) => "dialog-warning-symbolic",
+            Self::TypeChange(_) => "view-refresh-symbolic",
+        }
+    }
+}
+
+/// Work out what a sync of `sync_dir` would do. The check runs in the
+/// background so the UI keeps responding, while `button` shows a spinner in
+/// place of its content.
+fn plan_sync(
+    db: &DatabaseConnection,
+    remote: RemotesModel,
+    sync_dir: SyncDirsModel,
+    button: &Button,
+) -> Result<SyncPlan, SyncError> {
+    let child = button.child();
+    let spinner = Spinner::builder().spinning(true).build();
+    button.set_sensitive(false);
+    button.set_child(Some(&spinner));
+
+    let db = db.clone();
+    let plan = util::run_in_background(move || {
+        SyncEngine::new(
+            db,
+            remote,
+            sync_dir,
+            Arc::new(Mutex::new(false)),
+            boxed::Box::new(|_| ()),
+        )
+        .plan()
+    });
+
+    button.set_child(child.as_ref());
+    button.set_sensitive(true);
+    plan
+}
+
+/// Show a window listing everything a sync of `sync_dir` would do, so it can
+/// be reviewed first. `on_response` gets called once with whether the user
+/// accepted the plan, which closing the window counts as not doing.
+fn show_sync_plan(sync_dir: &SyncDirsModel, plan: &SyncPlan, on_response: impl Fn(bool) + 'static) {
+    let window = ApplicationWindow::builder()
+        .title(&util::get_title!("Sync Preview"))
+        .default_width(500)
+        .default_height(500)
+        .modal(true)
+        .build();
+    window.add_css_class("celeste-global-padding");
+    let sections = Box::builder().orientation(Orientation::Vertical).build();
+    sections.append(&HeaderBar::new());
+
+    let title = Label::builder()
+        .label(&tr::tr!(
+            "Syncing '{}' with '/{}' would do the following:",
+            util::fmt_home(&sync_dir.local_path),
+            sync_dir.remote_path
+        ))
+        .halign(Align::Start)
+        .wrap(true)
+        .css_classes(vec!["heading".to_string()])
+        .build();
+    let conflicts = plan
+        .items
+        .iter()
+        .filter(|item| matches!(item.action, PlanAction::Conflict(_)))
+        .count();
+    let summary = Label::builder()
+        .label(&tr::tr!(
+            "{} uploads, {} downloads, {} moves, {} local deletions, {} remote deletions and {} conflicts.",
+            plan.count(PlanAction::Upload),
+            plan.count(PlanAction::Download),
+            plan.count(PlanAction::Move),
+            plan.count(PlanAction::DeleteLocal),
+            plan.count(PlanAction::DeleteRemote),
+            conflicts
+        ))
+        .halign(Align::Start)
+        .wrap(true)
+        .margin_bottom(10)
+        .css_classes(vec!["dim-label".to_string()])
+        .build();
+    sections.append(&title);
+    sections.append(&summary);
+
+    if plan.is_empty() {
+        let empty_label = Label::builder()
+            .label(&tr::tr!("Both sides are already in sync."))
+            .vexpand(true)
+            .css_classes(vec!["dim-label".to_string()])
+            .build();
+        sections.append(&empty_label);
+    } else {
+        let list = ListBox::builder()
+            .selection_mode(SelectionMode::None)
+            .valign(Align::Start)
+            .css_classes(vec!["boxed-list".to_string()])
+            .build();
+
+        for error in &plan.errors {
+            list.append(&ListBoxRow::builder().child(&error.generate_ui()).build());
+        }
+
+        for item in &plan.items {
+            let path = if item.is_dir {
+                format!("{}/", item.path)
+            } else {
+                item.path.clone()
+            };
+            let row = ActionRow::builder()
+                .title(&path)
+                .subtitle(&item.action.label())
+                .build();
+            row.add_prefix(&Image::from_icon_name(item.action.icon_name()));
+            list.append(&row);
+        }
+
+        let scrolled = ScrolledWindow::builder()
+            .child(&list)
+            .vexpand(true)
+            .hscrollbar_policy(PolicyType::Never)
+            .build();
+        sections.append(&scrolled);
+    }
+
+    let confirm_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(10)
+        .margin_top(10)
+        .halign(Align::End)
+        .build();
+    let cancel_button = Button::with_label(&tr::tr!("Cancel"));
+    let accept_button = Button::builder()
+        .label(&tr::tr!("Sync"))
+        .css_classes(vec!["suggested-action".to_string()])
+        .build();
+    confirm_box.append(&cancel_button);
+    confirm_box.append(&accept_button);
+    sections.append(&confirm_box);
+
+    // Make sure `on_response` only gets called once, as closing the window after a
+    // response would otherwise count as cancelling.
+    let responded = Rc::new(Cell::new(false));
+    let on_response = Rc::new(on_response);
+    let respond = Rc::new(move |accepted: bool| {
+        if !responded.replace(true) {
+            on_response(accepted);
+        }
+    });
+    accept_button.connect_clicked(glib::clone!(@strong respond, @weak window => move |_| {
+        respond(true);
+        window.close();
+    }));
+    cancel_button.connect_clicked(glib::clone!(@strong respond, @weak window => move |_| {
+        respond(false);
+        window.close();
+    }));
+    window.connect_close_request(move |_| {
+        respond(false);
+        Inhibit(false)
+    });
+
+    window.set_content(Some(&sections));
+    window.show();
+}
+
+/// Get the label to show for a version storage in the UI.
+fn version_storage_label(storage: VersionStorage) -> String {
+    match storage {
+        VersionStorage::Off => tr::tr!("Off"),
+        VersionStorage::Local => tr::tr!("Local '{}' folder", VERSIONS_DIR_NAME),
+        VersionStorage::Remote => tr::tr!("Remote '{}' folder", VERSIONS_DIR_NAME),
+    }
+}
+
+/// Show a window listing the previous versions of the file at `local_path` in
+/// `sync_dir`, each of which can be restored.
+fn show_file_versions(db: &DatabaseConnection, sync_dir_id: i32, local_path: &str) {
+    // Use the current settings, as they may have changed since the page was
+    // created.
+    let Some(sync_dir) =
+        util::await_future(SyncDirsEntity::find_by_id(sync_dir_id).one(db)).unwrap()
+    else {
+        return;
+    };
+    let remote = util::await_future(RemotesEntity::find_by_id(sync_dir.remote_id).one(db))
+        .unwrap()
+        .unwrap();
+
+    if !local_path.starts_with(&format!("{}/", sync_dir.local_path)) {
+        gtk_util::show_error(
+            &tr::tr!("The file isn't inside of this directory."),
+            Some(&util::fmt_home(local_path)),
+        );
+        return;
+    }
+
+    let versions = Versions::new(&remote.name, sync_dir);
+    let file_versions = match versions.list(local_path) {
+        Ok(file_versions) => file_versions,
+        Err(err) => {
+            gtk_util::show_error(
+                &tr::tr!("Failed to get the previous versions of this file."),
+                Some(&err.message()),
+            );
+            return;
+        }
+    };
+
+    let window = ApplicationWindow::builder()
+        .title(&util::get_title!("File Versions"))
+        .default_width(500)
+        .default_height(400)
+        .modal(true)
+        .build();
+    window.add_css_class("celeste-global-padding");
+    let sections = Box::builder().orientation(Orientation::Vertical).build();
+    sections.append(&HeaderBar::new());
+
+    let title = Label::builder()
+        .label(&tr::tr!(
+            "Previous versions of '{}':",
+            util::fmt_home(local_path)
+        ))
+        .halign(Align::Start)
+        .wrap(true)
+        .margin_bottom(10)
+        .css_classes(vec!["heading".to_string()])
+        .build();
+    sections.append(&title);
+
+    if file_versions.is_empty() {
+        let empty_label = Label::builder()
+            .label(&tr::tr!("No previous versions have been kept."))
+            .vexpand(true)
+            .css_classes(vec!["dim-label".to_string()])
+            .build();
+        sections.append(&empty_label);
+    } else {
+        let list = ListBox::builder()
+            .selection_mode(SelectionMode::None)
+            .valign(Align::Start)
+            .css_classes(vec!["boxed-list".to_string()])
+            .build();
+        let versions = Rc::new(versions);
+        let local_path = local_path.to_owned();
+
+        for version in file_versions {
+            let time = glib::DateTime::from_unix_local(version.timestamp)
+                .and_then(|time| time.format("%c"))
+                .map(|time| time.to_string())
+                .unwrap_or_else(|_| version.timestamp.to_string());
+            let restore_button = Button::builder()
+                .label(&tr::tr!("Restore"))
+                .valign(Align::Center)
+                .build();
+            restore_button.connect_clicked(glib::clone!(@strong versions, @strong local_path, @weak window => move |button| {
+                button.set_sensitive(false);
+
+                match versions.restore(&version, &local_path) {
+                    Ok(()) => window.close(),
+                    Err(err) => {
+                        gtk_util::show_error(&tr::tr!("Failed to restore the version."), Some(&err.message()));
+                        button.set_sensitive(true);
+                    }
+                }
+            }));
+            let row = ActionRow::builder()
+                .title(&time)
+                .subtitle(&glib::format_size(version.size))
+                .build();
+            row.add_suffix(&restore_button);
+            list.append(&row);
+        }
+
+        let scrolled = ScrolledWindow::builder()
+            .child(&list)
+            .vexpand(true)
+            .hscrollbar_policy(PolicyType::Never)
+            .build();
+        sections.append(&scrolled);
+    }
+
+    window.set_content(Some(&sections));
+    window.show();
+}
+
+/// Show a window listing the remote subfolders of the sync directory with the
+/// ID of `sync_dir_id`, where the ones to leave out of syncing can be
+/// unchecked.
+fn show_selective_sync(db: &DatabaseConnection, sync_dir_id: i32) {
+    // Use the current settings, as they may have changed since the page was
+    // created.
+    let Some(sync_dir) =
+        util::await_future(SyncDirsEntity::find_by_id(sync_dir_id).one(db)).unwrap()
+    else {
+        return;
+    };
+    let remote = util::await_future(RemotesEntity::find_by_id(sync_dir.remote_id).one(db))
+        .unwrap()
+        .unwrap();
+    let items = match rclone::sync::list(
+        &remote.name,
+        &sync_dir.remote_path,
+        true,
+        RcloneListFilter::Dirs,
+    ) {
+        Ok(items) => items,
+        Err(err) => {
+            gtk_util::show_error(
+                &tr::tr!("Failed to get the folders on the remote."),
+                Some(&err.error),
+            );
+            return;
+        }
+    };
+
+    // The folders' paths relative to the roots, sorted so each folder comes right
+    // before the ones inside of it. The folders holding trashed items and previous
+    // versions never get synced anyway.
+    let versions = Versions::new(&remote.name, sync_dir.clone());
+    let trash_path = util::strip_slashes(&sync_dir.remote_trash_path);
+    let mut dirs: Vec<String> = items
+        .into_iter()
+        .filter(|item| {
+            !versions.contains_remote(&item.path)
+                && (trash_path.is_empty()
+                    || (item.path != trash_path
+                        && !item.path.starts_with(&format!("{trash_path}/"))))
+        })
+        .map(|item| {
+            if sync_dir.remote_path.is_empty() {
+                item.path
+            } else {
+                item.path
+                    .strip_prefix(&format!("{}/", sync_dir.remote_path))
+                    .unwrap()
+                    .to_owned()
+            }
+        })
+        .collect();
+    dirs.sort_by(|a, b| a.split('/').cmp(b.split('/')));
+    let old_skipped_dirs = sync::skipped_dirs(db, sync_dir.id);
+    let db = db.clone();
+
+    let window = ApplicationWindow::builder()
+        .title(&util::get_title!("Selective Sync"))
+        .default_width(500)
+        .default_height(500)
+        .modal(true)
+        .build();
+    window.add_css_class("celeste-global-padding");
+    let sections = Box::builder().orientation(Orientation::Vertical).build();
+    sections.append(&HeaderBar::new());
+
+    let title = Label::builder()
+        .label(&tr::tr!(
+            "Uncheck the folders in '/{}' that shouldn't be synced to '{}':",
+            sync_dir.remote_path,
+            util::fmt_home(&sync_dir.local_path)
+        ))
+        .halign(Align::Start)
+        .wrap(true)
+        .margin_bottom(10)
+        .css_classes(vec!["heading".to_string()])
+        .build();
+    sections.append(&title);
+
+    let list = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .valign(Align::Start)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    let check_buttons: Rc<Vec<(String, CheckButton)>> = Rc::new(
+        dirs.into_iter()
+            .map(|dir| {
+                let depth = dir.matches('/').count();
+                let name = dir.rsplit('/').next().unwrap().to_owned();
+                let skipped = old_skipped_dirs.iter().any(|skipped_dir| {
+                    dir == *skipped_dir || dir.starts_with(&format!("{skipped_dir}/"))
+                });
+                let check_button = CheckButton::builder()
+                    .label(&name)
+                    .active(!skipped)
+                    .margin_top(6)
+                    .margin_bottom(6)
+                    .margin_end(6)
+                    .margin_start(6 + depth as i32 * 20)
+                    .build();
+                list.append(&ListBoxRow::builder().child(&check_button).build());
+                (dir, check_button)
+            })
+            .collect(),
+    );
+
+    // The folders inside of an unchecked one aren't synced either, so they can't
+    // be changed.
+    let update_sensitivity = glib::clone!(@weak check_buttons => move || {
+        for (dir, check_button) in check_buttons.iter() {
+            let parent_unchecked = check_buttons.iter().any(|(other_dir, other_button)| dir.starts_with(&format!("{other_dir}/")) && !other_button.is_active());
+            check_button.set_sensitive(!parent_unchecked);
+        }
+    });
+    update_sensitivity();
+
+    for (_, check_button) in check_buttons.iter() {
+        check_button.connect_toggled(
+            glib::clone!(@strong update_sensitivity => move |_| update_sensitivity()),
+        );
+    }
+
+    if check_buttons.is_empty() {
+        let empty_label = Label::builder()
+            .label(&tr::tr!("There aren't any folders on the remote yet."))
+            .vexpand(true)
+            .css_classes(vec!["dim-label".to_string()])
+            .build();
+        sections.append(&empty_label);
+    } else {
+        let scrolled = ScrolledWindow::builder()
+            .child(&list)
+            .vexpand(true)
+            .hscrollbar_policy(PolicyType::Never)
+            .build();
+        sections.append(&scrolled);
+    }
+
+    let confirm_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(10)
+        .margin_top(10)
+        .halign(Align::End)
+        .build();
+    let cancel_button = Button::with_label(&tr::tr!("Cancel"));
+    let save_button = Button::builder()
+        .label(&tr::tr!("Save"))
+        .css_classes(vec!["suggested-action".to_string()])
+        .build();
+    confirm_box.append(&cancel_button);
+    confirm_box.append(&save_button);
+    sections.append(&confirm_box);
+
+    cancel_button.connect_clicked(glib::clone!(@weak window => move |_| {
+        window.close();
+    }));
+    save_button.connect_clicked(glib::clone!(@strong db, @strong sync_dir, @strong check_buttons, @weak window => move |_| {
+        // Only the outermost unchecked folders need to be stored, as everything inside of them is skipped along with them.
+        let skipped_dirs: Vec<String> = check_buttons.iter().filter(|(_, check_button)| !check_button.is_active() && check_button.is_sensitive()).map(|(dir, _)| dir.clone()).collect();
+        let removable_dirs: Vec<String> = skipped_dirs.iter().filter(|dir| !old_skipped_dirs.contains(dir) && Path::new(&format!("{}/{dir}", sync_dir.local_path)).exists()).cloned().collect();
+        sync::set_skipped_dirs(&db, &sync_dir, &skipped_dirs);
+        window.close();
+
+        if removable_dirs.is_empty() {
+            return;
+        }
+
+        // Offer to remove the local copies of the folders that were synced until now.
+        let dialog = MessageDialog::builder()
+            .text(&tr::tr!("Remove the local copies of the folders that are no longer synced?"))
+            .secondary_text(&tr::tr!("The following folders will be moved to the trash. They'll be kept on the remote.\n\n{}", removable_dirs.join("\n")))
+            .buttons(ButtonsType::YesNo)
+            .build();
+        dialog.connect_response(glib::clone!(@strong sync_dir, @strong removable_dirs => move |dialog, resp| {
+            dialog.close();
+
+            if resp != ResponseType::Yes {
+                return;
+            }
+
+            for dir in &removable_dirs {
+                if let Err(err) = sync::remove_local_copy(&sync_dir, dir) {
+                    gtk_util::show_error(&tr::tr!("Failed to remove the local copy of '{}'.", dir), Some(&err.message()));
+                }
+            }
+        }));
+        dialog.show();
+    }));
+
+    window.set_content(Some(&sections));
+    window.show();
+}
+
+/// The most entries to show on a remote's activity page at once.
+const ACTIVITY_LIMIT: u64 = 200;
+
+/// The number of recently synced items to show in the tray menu.
+const TRAY_RECENT_LIMIT: u64 = 10;
+
+impl SyncAction {
+    /// Get the label to show for this action in the activity log.
+    fn label(&self) -> String {
+        match self {
+            Self::Upload => tr::tr!("Uploaded"),
+            Self::Download => tr::tr!("Downloaded"),
+            Self::DeleteLocal => tr::tr!("Deleted locally"),
+            Self::DeleteRemote => tr::tr!("Deleted on remote"),
+            Self::Move => tr::tr!("Moved on remote"),
+        }
+    }
+
+    /// Get the name of the icon to show for this action in the activity log.
+    fn icon_name(&self) -> &'static str {
+        match self {
+            Self::Upload => "go-up-symbolic",
+            Self::Download => "go-down-symbolic",
+            Self::DeleteLocal | Self::DeleteRemote => "user-trash-symbolic",
+            Self::Move => "document-send-symbolic",
+        }
+    }
+}
+
+/// Get the entries in the activity log of the remote with the ID of
+/// `remote_id`, newest first. `path_filter` only keeps entries whose path
+/// contains it, while `actions` and `results` only keep entries with one of
+/// the given actions and results when not empty.
+fn get_activity(
+    db: &DatabaseConnection,
+    remote_id: i32,
+    path_filter: &str,
+    actions: &[SyncAction],
+    results: &[SyncResult],
+    limit: u64,
+) -> Vec<SyncEventsModel> {
+    let sync_dir_ids: Vec<i32> = util::await_future(
+        SyncDirsEntity::find()
+            .filter(SyncDirsColumn::RemoteId.eq(remote_id))
+            .all(db),
+    )
+    .unwrap()
+    .into_iter()
+    .map(|sync_dir| sync_dir.id)
+    .collect();
+    let mut query =
+        SyncEventsEntity::find().filter(SyncEventsColumn::SyncDirId.is_in(sync_dir_ids));
+
+    if !path_filter.is_empty() {
+        query = query.filter(SyncEventsColumn::Path.contains(path_filter));
+    }
+    if !actions.is_empty() {
+        query = query.filter(SyncEventsColumn::Action.is_in(actions.to_vec()));
+    }
+    if !results.is_empty() {
+        query = query.filter(SyncEventsColumn::Result.is_in(results.to_vec()));
+    }
+
+    util::await_future(
+        query
+            .order_by_desc(SyncEventsColumn::Timestamp)
+            .limit(limit)
+            .all(db),
+    )
+    .unwrap()
+}
+
+/// Get a description of an entry in the activity log, such as `Uploaded ·
+/// 1.2 MB · 10/18/26 14:02:11`.
+fn activity_label(event: &SyncEventsModel) -> String {
+    let mut parts = vec![event.action.label()];
+
+    if let Some(bytes) = event.bytes {
+        parts.push(glib::format_size(bytes as u64).to_string());
+    }
+    if let Ok(time) = glib::DateTime::from_unix_local(event.timestamp)
+        && let Ok(time) = time.format("%x %X")
+    {
+        parts.push(time.to_string());
+    }
+
+    match event.result {
+        SyncResult::Success => (),
+        SyncResult::Cancelled => parts.push(tr::tr!("Cancelled")),
+        SyncResult::Failed => parts.push(tr::tr!(
+            "Failed: {}",
+            event.error.as_deref().unwrap_or_default()
+        )),
+    }
+
+    parts.join(" · ")
+}
+
+/// Generate the page showing the activity log of the remote with the ID of
+/// `remote_id`, which goes back to the `main` page of `sections`. Returns the
+/// page along with a function to reload its entries.
+fn gen_activity_page(
+    db: &DatabaseConnection,
+    remote_id: i32,
+    sections: &Stack,
+) -> (Box, Rc<dyn Fn()>) {
+    let db = db.clone();
+    let page = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(10)
+        .css_classes(vec!["background".to_string()])
+        .build();
+
+    let header = Box::builder().orientation(Orientation::Horizontal).build();
+    let back_button = Button::builder()
+        .icon_name("go-previous-symbolic")
+        .halign(Align::Start)
+        .build();
+    back_button.connect_clicked(glib::clone!(@weak sections => move |_| {
+        // Temporarily reverse the transition direction so it looks like we're going back a page.
+        let previous_transition_type = sections.transition_type();
+        sections.set_transition_type(StackTransitionType::OverRight);
+        sections.set_visible_child_name("main");
+        sections.set_transition_type(previous_transition_type);
+    }));
+    let title = Label::builder()
+        .label(&tr::tr!("Activity"))
+        .hexpand(true)
+        .css_classes(vec!["heading".to_string()])
+        .build();
+    header.append(&back_button);
+    header.append(&title);
+    page.append(&header);
+
+    // The filters for the entries to show.
+    let filters = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(10)
+        .build();
+    let path_entry = SearchEntry::builder()
+        .placeholder_text(&tr::tr!("Filter by path"))
+        .hexpand(true)
+        .build();
+    let action_filters: Vec<(String, Vec<SyncAction>)> = vec![
+        (tr::tr!("All actions"), vec![]),
+        (tr::tr!("Uploads"), vec![SyncAction::Upload]),
+        (tr::tr!("Downloads"), vec![SyncAction::Download]),
+        (
+            tr::tr!("Deletions"),
+            vec![SyncAction::DeleteLocal, SyncAction::DeleteRemote],
+        ),
+        (tr::tr!("Moves"), vec![SyncAction::Move]),
+    ];
+    let result_filters: Vec<(String, Vec<SyncResult>)> = vec![
+        (tr::tr!("All results"), vec![]),
+        (tr::tr!("Succeeded"), vec![SyncResult::Success]),
+        (tr::tr!("Failed"), vec![SyncResult::Failed]),
+        (tr::tr!("Cancelled"), vec![SyncResult::Cancelled]),
+    ];
+    let action_labels: Vec<&str> = action_filters
+        .iter()
+        .map(|(label, _)| label.as_str())
+        .collect();
+    let result_labels: Vec<&str> = result_filters
+        .iter()
+        .map(|(label, _)| label.as_str())
+        .collect();
+    let action_dropdown = DropDown::builder()
+        .model(&StringList::new(&action_labels))
+        .build();
+    let result_dropdown = DropDown::builder()
+        .model(&StringList::new(&result_labels))
+        .build();
+    filters.append(&path_entry);
+    filters.append(&action_dropdown);
+    filters.append(&result_dropdown);
+    page.append(&filters);
+
+    let list = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .valign(Align::Start)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    let empty_label = Label::builder()
+        .label(&tr::tr!("Nothing has been synced yet."))
+        .vexpand(true)
+        .css_classes(vec!["dim-label".to_string()])
+        .build();
+    let scrolled = ScrolledWindow::builder()
+        .child(&list)
+        .vexpand(true)
+        .hscrollbar_policy(PolicyType::Never)
+        .build();
+    page.append(&empty_label);
+    page.append(&scrolled);
+
+    let reload: Rc<dyn Fn()> = Rc::new(
+        glib::clone!(@strong db, @weak list, @weak empty_label, @weak scrolled, @weak path_entry, @weak action_dropdown, @weak result_dropdown => move || {
+            while let Some(row) = list.row_at_index(0) {
+                list.remove(&row);
+            }
+
+            let (_, actions) = &action_filters[action_dropdown.selected() as usize];
+            let (_, results) = &result_filters[result_dropdown.selected() as usize];
+            let events = get_activity(&db, remote_id, path_entry.text().as_str(), actions, results, ACTIVITY_LIMIT);
+
+            for event in &events {
+                let container = Box::builder().orientation(Orientation::Horizontal).spacing(10).margin_top(6).margin_end(6).margin_bottom(6).margin_start(6).build();
+                let text_container = Box::builder().orientation(Orientation::Vertical).spacing(2).build();
+                let path_label = Label::builder().label(&util::fmt_home(&event.path)).halign(Align::Start).ellipsize(EllipsizeMode::Middle).build();
+                let details_label = Label::builder().label(&activity_label(event)).halign(Align::Start).wrap(true).css_classes(vec!["caption".to_string(), "dim-label".to_string()]).build();
+                let icon = Image::from_icon_name(event.action.icon_name());
+
+                if event.result != SyncResult::Success {
+                    icon.add_css_class("warning");
+                }
+
+                text_container.append(&path_label);
+                text_container.append(&details_label);
+                container.append(&icon);
+                container.append(&text_container);
+                list.append(&ListBoxRow::builder().child(&container).build());
+            }
+
+            empty_label.set_visible(events.is_empty());
+            scrolled.set_visible(!events.is_empty());
+        }),
+    );
+    path_entry.connect_search_changed(glib::clone!(@strong reload => move |_| reload()));
+    action_dropdown.connect_selected_notify(glib::clone!(@strong reload => move |_| reload()));
+    result_dropdown.connect_selected_notify(glib::clone!(@strong reload => move |_| reload()));
+
+    (page, reload)
+}
+
 pub fn launch(app: &Application, background: bool) {
     // Create the configuration directory if it doesn't exist.
     let config_path = util::get_config_dir();
@@ -229,8 +1185,18 @@ pub fn launch(app: &Application, background: bool) {
     // a queue so they can be processed when syncing is at a good point of stopping.
     let sync_dir_deletion_queue: SyncDirDeletionQueue = Rc::new(RefCell::new(vec![]));
 
+    // The names of the remotes the user asked to check for changes right away.
+    let check_requests: Rc<RefCell<HashSet<String>>> = Rc::new(RefCell::new(HashSet::new()));
+
+    // The names of the remotes that were already offered to log in again after
+    // their login expired.
+    let reconnect_offers: Rc<RefCell<HashSet<String>>> = Rc::new(RefCell::new(HashSet::new()));
+
+    // The storage usage shown on each remote's page.
+    let quota_displays: QuotaMap = Rc::new(RefCell::new(HashMap::new()));
+
     // Add servers.
-    let gen_remote_window = glib::clone!(@strong window, @strong remote_deletion_queue, @strong sync_dir_deletion_queue, @strong directory_map, @strong db => move |remote: RemotesModel| {
+    let gen_remote_window = glib::clone!(@strong window, @strong remote_deletion_queue, @strong sync_dir_deletion_queue, @strong directory_map, @strong db, @strong check_requests, @strong reconnect_offers, @strong quota_displays => move |remote: RemotesModel| {
         let remote_name = remote.name;
 
         // The stack containing the window of sync status', as well as extra information for each sync pair.
@@ -254,7 +1220,7 @@ pub fn launch(app: &Application, background: bool) {
             .build();
 
         // Add a directory to the stack.
-        let add_dir = glib::clone!(@weak window, @weak sections, @weak page, @weak sync_dirs, @strong remote_name, @strong directory_map, @strong sync_dir_deletion_queue => move |
+        let add_dir = glib::clone!(@weak window, @weak sections, @weak page, @weak sync_dirs, @strong remote_name, @strong directory_map, @strong sync_dir_deletion_queue, @strong db, @strong check_requests => move |
             server_name: String,
             local_path: String,
             remote_path: String,
@@ -263,6 +1229,21 @@ pub fn launch(app: &Application, background: bool) {
             let formatted_local_path = util::fmt_home(&local_path);
             let formatted_remote_path = format!("/{remote_path}");
 
+            let db_sync_dir = util::await_future(
+                SyncDirsEntity::find().filter(SyncDirsColumn::LocalPath.eq(local_path.clone())).filter(SyncDirsColumn::RemotePath.eq(remote_path.clone())).one(&db)
+            ).unwrap().unwrap();
+
+            // Save a change to this sync directory's settings.
+            let update_sync_dir = Rc::new(glib::clone!(@strong db, @strong db_sync_dir => move |update: &dyn Fn(&mut SyncDirsActiveModel)| {
+                let mut active_model: SyncDirsActiveModel = db_sync_dir.clone().into();
+                update(&mut active_model);
+
+                // The sync directory may have just been removed, in which case there's nothing left to update.
+                if db_sync_dir.exists(&db) {
+                    util::await_future(active_model.update(&db)).unwrap();
+                }
+            }));
+
             // The sync status row.
             let sync_status_sections = Box::builder().orientation(Orientation::Vertical).margin_start(10).margin_end(10).build();
             let row_sections = Box::builder().orientation(Orientation::Horizontal).build();
@@ -299,13 +1280,40 @@ pub fn launch(app: &Application, background: bool) {
 
             row_sections.append(&text_sections);
 
+            // The button to pause and resume syncing this directory.
+            let paused = Rc::new(Cell::new(db_sync_dir.paused));
+            let pause_button = Button::builder()
+                .halign(Align::End)
+                .valign(Align::Center)
+                .hexpand_set(true)
+                .hexpand(true)
+                .css_classes(vec!["flat".to_string()])
+                .build();
+            let update_pause_button = glib::clone!(@weak pause_button, @strong paused => move || {
+                if paused.get() {
+                    pause_button.set_icon_name("media-playback-start-symbolic");
+                    pause_button.set_tooltip_text(Some(&tr::tr!("Resume syncing this directory")));
+                } else {
+                    pause_button.set_icon_name("media-playback-pause-symbolic");
+                    pause_button.set_tooltip_text(Some(&tr::tr!("Pause syncing this directory")));
+                }
+            });
+            update_pause_button();
+            pause_button.connect_clicked(glib::clone!(@strong paused, @strong update_sync_dir, @strong update_pause_button, @strong check_requests, @strong remote_name => move |_| {
+                paused.set(!paused.get());
+                update_sync_dir(&|active_model| active_model.paused = ActiveValue::Set(paused.get()));
+                update_pause_button();
+
+                // Check the remote right away, so the directory's status reflects the change.
+                check_requests.get_mut_ref().insert(remote_name.clone());
+            }));
+
             let more_info_button = Image::builder()
                 .icon_name("go-next-symbolic")
                 .halign(Align::End)
-                .hexpand_set(true)
-                .hexpand(true)
                 .build();
 
+            row_sections.append(&pause_button);
             row_sections.append(&more_info_button);
             sync_status_sections.append(&row_sections);
 
@@ -321,6 +1329,18 @@ pub fn launch(app: &Application, background: bool) {
                 .margin_bottom(10)
                 .build();
 
+            // The running transfers, each of which can be cancelled.
+            let more_info_transfers = Box::builder().orientation(Orientation::Vertical).visible(false).margin_bottom(10).build();
+            let more_info_transfers_label = Label::builder()
+                .label(&tr::tr!("Transfers"))
+                .halign(Align::Start)
+                .margin_bottom(10)
+                .css_classes(vec!["heading".to_string()])
+                .build();
+            let more_info_transfers_list = ListBox::builder().selection_mode(SelectionMode::None).css_classes(vec!["boxed-list".to_string()]).margin_top(5).margin_end(5).margin_bottom(5).margin_start(5).build();
+            more_info_transfers.append(&more_info_transfers_label);
+            more_info_transfers.append(&more_info_transfers_list);
+
             // The errors section.
             let more_info_errors_label = Label::builder()
             .label(&tr::tr!("Sync Errors"))
@@ -335,6 +1355,145 @@ pub fn launch(app: &Application, background: bool) {
             let more_info_errors_list = ListBox::builder().selection_mode(SelectionMode::None).css_classes(vec!["boxed-list".to_string()]).margin_top(5).margin_end(5).margin_bottom(5).margin_start(5).build();
             let more_info_errors_list_scrolled = ScrolledWindow::builder().child(&more_info_errors_list).valign(Align::Start).visible(false).build();
 
+            // The sync directory's settings.
+            let more_info_settings_label = Label::builder()
+                .label(&tr::tr!("Settings"))
+                .halign(Align::Start)
+                .margin_top(20)
+                .margin_bottom(10)
+                .css_classes(vec!["heading".to_string()])
+                .build();
+            let more_info_settings_list = ListBox::builder().selection_mode(SelectionMode::None).css_classes(vec!["boxed-list".to_string()]).margin_top(5).margin_end(5).margin_bottom(5).margin_start(5).build();
+            let conflict_policies: Vec<ConflictPolicy> = ConflictPolicy::iter().collect();
+            let conflict_policy_labels: Vec<String> = conflict_policies.iter().map(|policy| conflict_policy_label(*policy)).collect();
+            let conflict_policy_row = ComboRow::builder()
+                .title(&tr::tr!("Conflict Policy"))
+                .subtitle(&tr::tr!("What to do when an item changed both locally and on the remote"))
+                .model(&StringList::new(&conflict_policy_labels.iter().map(|label| label.as_str()).collect::<Vec<&str>>()))
+                .build();
+            conflict_policy_row.set_selected(conflict_policies.iter().position(|policy| *policy == db_sync_dir.conflict_policy).unwrap() as u32);
+            conflict_policy_row.connect_selected_notify(glib::clone!(@strong update_sync_dir, @strong conflict_policies => move |row| {
+                update_sync_dir(&|active_model| active_model.conflict_policy = ActiveValue::Set(conflict_policies[row.selected() as usize]));
+            }));
+            let remote_trash_row = EntryRow::builder()
+                .title(&tr::tr!("Remote trash folder (empty to delete permanently)"))
+                .text(&db_sync_dir.remote_trash_path)
+                .show_apply_button(true)
+                .build();
+            remote_trash_row.connect_apply(glib::clone!(@strong update_sync_dir => move |row| {
+                let trash_path = util::strip_slashes(row.text().as_str());
+                row.set_text(&trash_path);
+                update_sync_dir(&|active_model| active_model.remote_trash_path = ActiveValue::Set(trash_path.clone()));
+            }));
+            let mass_deletion_percent_button = SpinButton::with_range(1.0, 100.0, 1.0);
+            mass_deletion_percent_button.set_valign(Align::Center);
+            mass_deletion_percent_button.set_value(db_sync_dir.mass_deletion_percent as f64);
+            mass_deletion_percent_button.connect_value_changed(glib::clone!(@strong update_sync_dir => move |button| {
+                update_sync_dir(&|active_model| active_model.mass_deletion_percent = ActiveValue::Set(button.value_as_int()));
+            }));
+            let mass_deletion_percent_row = ActionRow::builder()
+                .title(&tr::tr!("Mass Deletion Percentage"))
+                .subtitle(&tr::tr!("Ask before deleting more than this percentage of the synced items"))
+                .build();
+            mass_deletion_percent_row.add_suffix(&mass_deletion_percent_button);
+            let mass_deletion_count_button = SpinButton::with_range(1.0, 1_000_000.0, 1.0);
+            mass_deletion_count_button.set_valign(Align::Center);
+            mass_deletion_count_button.set_value(db_sync_dir.mass_deletion_count as f64);
+            mass_deletion_count_button.connect_value_changed(glib::clone!(@strong update_sync_dir => move |button| {
+                update_sync_dir(&|active_model| active_model.mass_deletion_count = ActiveValue::Set(button.value_as_int()));
+            }));
+            let mass_deletion_count_row = ActionRow::builder()
+                .title(&tr::tr!("Mass Deletion Count"))
+                .subtitle(&tr::tr!("Ask before deleting more than this many items"))
+                .build();
+            mass_deletion_count_row.add_suffix(&mass_deletion_count_button);
+            let version_storages: Vec<VersionStorage> = VersionStorage::iter().collect();
+            let version_storage_labels: Vec<String> = version_storages.iter().map(|storage| version_storage_label(*storage)).collect();
+            let version_storage_row = ComboRow::builder()
+                .title(&tr::tr!("Versioning"))
+                .subtitle(&tr::tr!("Where to keep the previous versions of files replaced while syncing"))
+                .model(&StringList::new(&version_storage_labels.iter().map(|label| label.as_str()).collect::<Vec<&str>>()))
+                .build();
+            version_storage_row.set_selected(version_storages.iter().position(|storage| *storage == db_sync_dir.version_storage).unwrap() as u32);
+            version_storage_row.connect_selected_notify(glib::clone!(@strong update_sync_dir, @strong version_storages => move |row| {
+                update_sync_dir(&|active_model| active_model.version_storage = ActiveValue::Set(version_storages[row.selected() as usize]));
+            }));
+            let max_versions_button = SpinButton::with_range(0.0, 1000.0, 1.0);
+            max_versions_button.set_valign(Align::Center);
+            max_versions_button.set_value(db_sync_dir.max_versions as f64);
+            max_versions_button.connect_value_changed(glib::clone!(@strong update_sync_dir => move |button| {
+                update_sync_dir(&|active_model| active_model.max_versions = ActiveValue::Set(button.value_as_int()));
+            }));
+            let max_versions_row = ActionRow::builder()
+                .title(&tr::tr!("Versions to Keep"))
+                .subtitle(&tr::tr!("The number of previous versions to keep for each file, or 0 for no limit"))
+                .build();
+            max_versions_row.add_suffix(&max_versions_button);
+            let max_version_days_button = SpinButton::with_range(0.0, 3650.0, 1.0);
+            max_version_days_button.set_valign(Align::Center);
+            max_version_days_button.set_value(db_sync_dir.max_version_days as f64);
+            max_version_days_button.connect_value_changed(glib::clone!(@strong update_sync_dir => move |button| {
+                update_sync_dir(&|active_model| active_model.max_version_days = ActiveValue::Set(button.value_as_int()));
+            }));
+            let max_version_days_row = ActionRow::builder()
+                .title(&tr::tr!("Days to Keep Versions"))
+                .subtitle(&tr::tr!("The number of days to keep previous versions for, or 0 for no limit"))
+                .build();
+            max_version_days_row.add_suffix(&max_version_days_button);
+            let selective_sync_button = Button::builder()
+                .label(&tr::tr!("Choose Folders"))
+                .valign(Align::Center)
+                .build();
+            selective_sync_button.connect_clicked(glib::clone!(@strong db, @strong db_sync_dir => move |_| {
+                show_selective_sync(&db, db_sync_dir.id);
+            }));
+            let selective_sync_row = ActionRow::builder()
+                .title(&tr::tr!("Selective Sync"))
+                .subtitle(&tr::tr!("Choose the remote folders to sync, leaving the others only on the remote"))
+                .build();
+            selective_sync_row.add_suffix(&selective_sync_button);
+            let restore_version_button = Button::builder()
+                .label(&tr::tr!("Choose File"))
+                .valign(Align::Center)
+                .build();
+            restore_version_button.connect_clicked(glib::clone!(@strong db, @strong db_sync_dir, @strong local_path => move |_| {
+                let dialog = FileChooserDialog::builder()
+                    .title(&util::get_title!("Restore Version"))
+                    .select_multiple(false)
+                    .modal(true)
+                    .build();
+                let _ = dialog.set_current_folder(Some(&gio::File::for_path(&local_path)));
+                let cancel_button = Button::with_label(&tr::tr!("Cancel"));
+                let ok_button = Button::with_label(&tr::tr!("Ok"));
+                dialog.add_action_widget(&cancel_button, ResponseType::Cancel);
+                dialog.add_action_widget(&ok_button, ResponseType::Ok);
+                cancel_button.connect_clicked(glib::clone!(@weak dialog => move |_| {
+                    dialog.close();
+                }));
+                ok_button.connect_clicked(glib::clone!(@strong db, @strong db_sync_dir, @weak dialog => move |_| {
+                    let Some(path) = dialog.file().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    dialog.close();
+                    show_file_versions(&db, db_sync_dir.id, &path.to_string_lossy());
+                }));
+                dialog.show();
+            }));
+            let restore_version_row = ActionRow::builder()
+                .title(&tr::tr!("Restore Version"))
+                .subtitle(&tr::tr!("Replace a file with one of its previous versions, which then gets synced like any other change"))
+                .build();
+            restore_version_row.add_suffix(&restore_version_button);
+            more_info_settings_list.append(&conflict_policy_row);
+            more_info_settings_list.append(&remote_trash_row);
+            more_info_settings_list.append(&mass_deletion_percent_row);
+            more_info_settings_list.append(&mass_deletion_count_row);
+            more_info_settings_list.append(&selective_sync_row);
+            more_info_settings_list.append(&version_storage_row);
+            more_info_settings_list.append(&max_versions_row);
+            more_info_settings_list.append(&max_version_days_row);
+            more_info_settings_list.append(&restore_version_row);
+
             // The exclusion list.
             let more_info_exclusions_header = Box::builder().orientation(Orientation::Horizontal).margin_top(20).margin_bottom(10).build();
             let more_info_exclusions_label = Label::builder()
@@ -461,13 +1620,47 @@ pub fn launch(app: &Application, background: bool) {
                 .halign(Align::End)
                 .build();
 
+            let more_info_preview_button = Button::builder()
+                .icon_name("edit-find-symbolic")
+                .has_tooltip(true)
+                .tooltip_text(&tr::tr!("Preview what syncing this directory would do"))
+                .halign(Align::End)
+                .margin_end(10)
+                .build();
+            more_info_preview_button.connect_clicked(glib::clone!(@strong db, @strong db_sync_dir, @strong remote_name, @strong paused, @strong update_sync_dir, @strong update_pause_button, @strong check_requests => move |button| {
+                // Use the current settings, as they may have changed since the page was created.
+                let Some(sync_dir) = util::await_future(SyncDirsEntity::find_by_id(db_sync_dir.id).one(&db)).unwrap() else {
+                    return;
+                };
+                let remote = util::await_future(RemotesEntity::find_by_id(sync_dir.remote_id).one(&db)).unwrap().unwrap();
+
+                match plan_sync(&db, remote, sync_dir.clone(), button) {
+                    Ok(plan) => show_sync_plan(&sync_dir, &plan, glib::clone!(@strong remote_name, @strong paused, @strong update_sync_dir, @strong update_pause_button, @strong check_requests => move |accepted| {
+                        if !accepted {
+                            return;
+                        }
+
+                        // The user asked for the sync to happen, so resume the directory if it's paused.
+                        if paused.replace(false) {
+                            update_sync_dir(&|active_model| active_model.paused = ActiveValue::Set(false));
+                            update_pause_button();
+                        }
+
+                        check_requests.get_mut_ref().insert(remote_name.clone());
+                    })),
+                    Err(err) => gtk_util::show_error(&tr::tr!("Failed to check what syncing this directory would do."), Some(&err.message())),
+                }
+            }));
             // Store the pages element's in a vector. When the delete button is pressed and we confirm a deletion, we want the entire page to not be sensitive except for the back button, and we do that by only making the back button sensitive.
             let more_info_widgets: Vec<Widget> = vec![
                 more_info_errors_label.clone().into(),
                 more_info_errors_list_scrolled.clone().into(),
+                more_info_settings_label.clone().into(),
+                more_info_settings_list.clone().into(),
                 more_info_exclusions_header.clone().into(),
                 more_info_exclusions_list_scrolled.clone().into(),
                 more_info_back_button.clone().into(),
+                more_info_preview_button.clone().into(),
                 more_info_delete_button.clone().into(),
             ];
             more_info_delete_button.connect_clicked(glib::clone!(@strong sync_dir_deletion_queue, @strong server_name, @strong local_path, @strong remote_path, @strong formatted_local_path, @strong formatted_remote_path, @weak sections, @weak more_info_back_button, @weak more_info_delete_button, @strong more_info_widgets => move |_| {
@@ -498,10 +1691,14 @@ pub fn launch(app: &Application, background: bool) {
                 dialog.show();
             }));
             more_info_header_buttons.append(&more_info_back_button);
+            more_info_header_buttons.append(&more_info_preview_button);
             more_info_header_buttons.append(&more_info_delete_button);
             more_info_page.append(&more_info_header_buttons);
+            more_info_page.append(&more_info_transfers);
             more_info_page.append(&more_info_errors_label);
             more_info_page.append(&more_info_errors_list_scrolled);
+            more_info_page.append(&more_info_settings_label);
+            more_info_page.append(&more_info_settings_list);
             more_info_page.append(&more_info_exclusions_header);
             more_info_page.append(&more_info_exclusions_list_scrolled);
 
@@ -553,7 +1750,10 @@ pub fn launch(app: &Application, background: bool) {
                     status_text: status,
                     error_list: more_info_errors_list,
                     error_items: HashMap::new(),
-                    update_error_ui: boxed::Box::new(update_error_list)
+                    update_error_ui: boxed::Box::new(update_error_list),
+                    transfer_container: more_info_transfers,
+                    transfer_list: more_info_transfers_list,
+                    transfer_rows: HashMap::new(),
                 }
             );
 
@@ -569,7 +1769,32 @@ pub fn launch(app: &Application, background: bool) {
             )
             .unwrap().unwrap();
 
-        // The directory header, directory addition button, and remote deletion button.
+        // The remote's storage usage, shown once it's known.
+        {
+            let container = Box::builder()
+                .orientation(Orientation::Vertical)
+                .spacing(6)
+                .margin_bottom(20)
+                .visible(false)
+                .build();
+            let label = Label::builder()
+                .halign(Align::Start)
+                .css_classes(vec!["dim-label".to_string()])
+                .build();
+            let level = LevelBar::new();
+
+            // The default offsets color the bar as if filling it up were a good thing.
+            for offset in ["low", "high", "full"] {
+                level.remove_offset_value(Some(offset));
+            }
+
+            container.append(&label);
+            container.append(&level);
+            page.append(&container);
+            quota_displays.get_mut_ref().insert(remote_name.clone(), QuotaDisplay { container, label, level });
+        }
+
+        // The directory header, activity button, check button, directory addition button, and remote deletion button.
         {
             let section = Box::builder().orientation(Orientation::Horizontal).build();
             let label = Label::builder()
@@ -773,6 +1998,17 @@ pub fn launch(app: &Application, background: bool) {
                 folder_sections.append(&Separator::builder().orientation(Orientation::Vertical).css_classes(vec!["spacer".to_string()]).build());
                 folder_sections.append(&remote_label);
                 folder_sections.append(&remote_entry);
+
+                // Get the direction to sync in.
+                let direction_label = Label::builder().label(&tr::tr!("Sync direction:")).halign(Align::Start).css_classes(vec!["heading".to_string()]).build();
+                let sync_directions: Vec<SyncDirection> = SyncDirection::iter().collect();
+                let sync_direction_labels: Vec<String> = sync_directions.iter().map(|direction| sync_direction_label(*direction)).collect();
+                let direction_dropdown = DropDown::builder()
+                    .model(&StringList::new(&sync_direction_labels.iter().map(|label| label.as_str()).collect::<Vec<&str>>()))
+                    .build();
+                folder_sections.append(&Separator::builder().orientation(Orientation::Vertical).css_classes(vec!["spacer".to_string()]).build());
+                folder_sections.append(&direction_label);
+                folder_sections.append(&direction_dropdown);
                 let confirm_box = Box::builder().orientation(Orientation::Horizontal).spacing(10).halign(Align::End).build();
                 let cancel_button = Button::with_label(&tr::tr!("Cancel"));
                 let ok_button = Button::with_label(&tr::tr!("Ok"));
@@ -808,7 +2044,7 @@ pub fn launch(app: &Application, background: bool) {
                     folder_window.close();
                     window.set_sensitive(true);
                 }));
-                ok_button.connect_clicked(glib::clone!(@strong window, @weak sections, @weak folder_window, @weak sync_dirs, @weak local_entry, @weak remote_entry, @strong db_remote, @strong db, @weak directory_map, @strong remote_name, @strong add_dir => move |_| {
+                ok_button.connect_clicked(glib::clone!(@strong window, @weak sections, @weak folder_window, @weak sync_dirs, @weak local_entry, @weak remote_entry, @weak direction_dropdown, @strong sync_directions, @strong db_remote, @strong db, @weak directory_map, @strong remote_name, @strong add_dir => move |ok_button| {
                     folder_window.set_sensitive(false);
 
                     // The local path needs to start with a slash, but not end with one. The remote
@@ -850,16 +2086,40 @@ pub fn launch(app: &Application, background: bool) {
                         gtk_util::show_error(&tr::tr!("The specified local directory needs to be an absolute path"), None);
                         folder_window.set_sensitive(true);
                     } else {
-                        util::await_future(
+                        // Add the directory paused, so it doesn't get synced until the user has
+                        // reviewed what the first sync would do.
+                        let sync_dir = util::await_future(
                             SyncDirsActiveModel {
                                 remote_id: ActiveValue::Set(db_remote.id),
                                 local_path: ActiveValue::Set(local_text.clone()),
                                 remote_path: ActiveValue::Set(remote_text.clone()),
+                                sync_direction: ActiveValue::Set(sync_directions[direction_dropdown.selected() as usize]),
+                                paused: ActiveValue::Set(true),
                                 ..Default::default()
                             }.insert(&db)
                         ).unwrap();
-                        add_dir(remote_name.clone(), local_text, remote_text);
-                        folder_window.close();
+                        let plan = match plan_sync(&db, db_remote.clone(), sync_dir.clone(), ok_button) {
+                            Ok(plan) => plan,
+                            Err(err) => {
+                                util::await_future(sync_dir.delete(&db)).unwrap();
+                                gtk_util::show_error(&tr::tr!("Failed to check what syncing the directories would do."), Some(&err.message()));
+                                folder_window.set_sensitive(true);
+                                return;
+                            }
+                        };
+
+                        show_sync_plan(&sync_dir, &plan, glib::clone!(@strong db, @strong sync_dir, @strong remote_name, @strong add_dir, @weak folder_window => move |accepted| {
+                            if accepted {
+                                let mut active_model: SyncDirsActiveModel = sync_dir.clone().into();
+                                active_model.paused = ActiveValue::Set(false);
+                                util::await_future(active_model.update(&db)).unwrap();
+                                add_dir(remote_name.clone(), sync_dir.local_path.clone(), sync_dir.remote_path.clone());
+                                folder_window.close();
+                            } else {
+                                util::await_future(sync_dir.clone().delete(&db)).unwrap();
+                                folder_window.set_sensitive(true);
+                            }
+                        }));
                     }
                 }));
 
@@ -894,7 +2154,32 @@ pub fn launch(app: &Application, background: bool) {
                 }));
                 dialog.show();
             }));
+            let check_button = Button::builder()
+                .icon_name("view-refresh-symbolic")
+                .tooltip_text(&tr::tr!("Check for changes now"))
+                .halign(Align::End)
+                .valign(Align::Start)
+                .margin_end(10)
+                .build();
+            check_button.connect_clicked(glib::clone!(@strong check_requests, @strong remote_name => move |_| {
+                check_requests.get_mut_ref().insert(remote_name.clone());
+            }));
+            let (activity_page, reload_activity) = gen_activity_page(&db, db_remote.id, &sections);
+            sections.add_named(&activity_page, Some("activity"));
+            let activity_button = Button::builder()
+                .icon_name("document-open-recent-symbolic")
+                .tooltip_text(&tr::tr!("Show what was synced recently"))
+                .halign(Align::End)
+                .valign(Align::Start)
+                .margin_end(10)
+                .build();
+            activity_button.connect_clicked(glib::clone!(@weak sections => move |_| {
+                reload_activity();
+                sections.set_visible_child_name("activity");
+            }));
             section.append(&label);
+            section.append(&activity_button);
+            section.append(&check_button);
             section.append(&new_folder_button);
             section.append(&delete_remote_button);
             page.append(&section);
@@ -921,6 +2206,83 @@ pub fn launch(app: &Application, background: bool) {
         page.append(&gtk_util::separator());
         page.append(&sync_dirs);
 
+        // The remote's settings.
+        {
+            let settings_label = Label::builder()
+                .label(&tr::tr!("Settings"))
+                .halign(Align::Start)
+                .margin_top(20)
+                .margin_bottom(10)
+                .css_classes(vec!["heading".to_string()])
+                .build();
+            let settings_list = ListBox::builder().selection_mode(SelectionMode::None).css_classes(vec!["boxed-list".to_string()]).margin_top(5).margin_end(5).margin_bottom(5).margin_start(5).build();
+
+            // Save a change to this remote's settings.
+            let update_remote = Rc::new(glib::clone!(@strong db, @strong db_remote => move |update: &dyn Fn(&mut RemotesActiveModel)| {
+                let mut active_model: RemotesActiveModel = db_remote.clone().into();
+                update(&mut active_model);
+
+                // The remote may have just been removed, in which case there's nothing left to update.
+                if db_remote.exists(&db) {
+                    util::await_future(active_model.update(&db)).unwrap();
+                }
+            }));
+            let poll_interval_button = SpinButton::with_range(5.0, 3600.0, 5.0);
+            poll_interval_button.set_valign(Align::Center);
+            poll_interval_button.set_value(db_remote.poll_interval as f64);
+            poll_interval_button.connect_value_changed(glib::clone!(@strong update_remote => move |button| {
+                update_remote(&|active_model| active_model.poll_interval = ActiveValue::Set(button.value_as_int()));
+            }));
+            let poll_interval_row = ActionRow::builder()
+                .title(&tr::tr!("Check Interval"))
+                .subtitle(&tr::tr!("Seconds between checks for changes, growing longer while nothing changes"))
+                .build();
+            poll_interval_row.add_suffix(&poll_interval_button);
+            let max_transfers_button = SpinButton::with_range(1.0, 32.0, 1.0);
+            max_transfers_button.set_valign(Align::Center);
+            max_transfers_button.set_value(db_remote.max_transfers as f64);
+            max_transfers_button.connect_value_changed(glib::clone!(@strong update_remote => move |button| {
+                update_remote(&|active_model| active_model.max_transfers = ActiveValue::Set(button.value_as_int()));
+            }));
+            let max_transfers_row = ActionRow::builder()
+                .title(&tr::tr!("Parallel Transfers"))
+                .subtitle(&tr::tr!("The number of files to transfer at once"))
+                .build();
+            max_transfers_row.add_suffix(&max_transfers_button);
+            let reconnect_button = Button::builder()
+                .label(&tr::tr!("Reconnect"))
+                .valign(Align::Center)
+                .build();
+            reconnect_button.connect_clicked(glib::clone!(@weak window, @strong remote_name, @strong check_requests, @strong reconnect_offers => move |_| {
+                if login::reconnect(&window.application().unwrap(), &remote_name) {
+                    reconnect_offers.get_mut_ref().remove(&remote_name);
+                    check_requests.get_mut_ref().insert(remote_name.clone());
+                }
+            }));
+            let reconnect_row = ActionRow::builder()
+                .title(&tr::tr!("Login"))
+                .subtitle(&tr::tr!("Log in again while keeping all synced folders, i.e. after changing your password"))
+                .build();
+            reconnect_row.add_suffix(&reconnect_button);
+            let bandwidth_row = EntryRow::builder()
+                .title(&tr::tr!("Bandwidth limit (empty to use the global limit)"))
+                .text(db_remote.bandwidth_limit.as_deref().unwrap_or_default())
+                .show_apply_button(true)
+                .build();
+            bandwidth_row.connect_apply(glib::clone!(@strong update_remote => move |row| {
+                let Some(schedule) = preferences::validate_bandwidth_limit(row) else {
+                    return;
+                };
+                update_remote(&|active_model| active_model.bandwidth_limit = ActiveValue::Set((!schedule.is_empty()).then(|| schedule.clone())));
+            }));
+            settings_list.append(&poll_interval_row);
+            settings_list.append(&max_transfers_row);
+            settings_list.append(&bandwidth_row);
+            settings_list.append(&reconnect_row);
+            page.append(&settings_label);
+            page.append(&settings_list);
+        }
+
         sections.add_named(&page, Some("main"));
         sections.set_visible_child_name("main");
         sections
@@ -971,6 +2333,16 @@ pub fn launch(app: &Application, background: bool) {
             crate::about::about_window(&app);
         }),
     );
+    let sidebar_menu_preferences_button = Button::builder()
+        .label(&tr::tr!("Preferences"))
+        .css_classes(vec!["flat".to_string()])
+        .build();
+    sidebar_menu_preferences_button.connect_clicked(
+        glib::clone!(@weak app, @weak sidebar_menu_popover, @strong db => move |_| {
+            sidebar_menu_popover.popdown();
+            preferences::preferences_window(&app, &db);
+        }),
+    );
     let sidebar_menu_quit_button = Button::builder()
         .label("Quit")
         .css_classes(vec!["flat".to_string()])
@@ -979,6 +2351,7 @@ pub fn launch(app: &Application, background: bool) {
         sidebar_menu_popover.popdown();
         *(*CLOSE_REQUEST).lock().unwrap() = true;
     }));
+    sidebar_menu_popover_sections.append(&sidebar_menu_preferences_button);
     sidebar_menu_popover_sections.append(&sidebar_menu_about_button);
     sidebar_menu_popover_sections.append(&sidebar_menu_quit_button);
     sidebar_menu_popover.set_parent(&sidebar_menu_button);
@@ -1078,7 +2451,62 @@ pub fn launch(app: &Application, background: bool) {
         error_count
     });
 
+    // The sync directories whose mass deletions were confirmed by the user, so that
+    // their next sync is allowed to go through with them.
+    let confirmed_deletions: Rc<RefCell<HashSet<i32>>> = Rc::new(RefCell::new(HashSet::new()));
+
+    // The local change watchers for each sync directory, keyed by the sync
+    // directory's ID.
+    let mut watchers: HashMap<i32, LocalWatcher> = HashMap::new();
+
+    // The bandwidth limit currently set in Rclone.
+    let mut bandwidth_limit: Option<String> = None;
+
+    // The bandwidth limits that turned out to be invalid and were reported to
+    // the user, keyed by the ID of their remote, or `None` for the global one.
+    let mut invalid_bandwidth_limits: HashSet<Option<i32>> = HashSet::new();
+
+    // When to check each remote for changes next, keyed by the remote's ID.
+    let mut pollers: HashMap<i32, PollScheduler> = HashMap::new();
+
+    // The sync directories whose local or remote root is missing.
+    let mut missing_roots: HashSet<i32> = HashSet::new();
+
+    // The names of the remotes that can't be reached, keyed by the remote's ID.
+    let mut offline_remotes: HashMap<i32, String> = HashMap::new();
+
+    // When each remote's storage usage was last checked, keyed by the remote's
+    // ID.
+    let mut quota_checks: HashMap<i32, Instant> = HashMap::new();
+
+    // The storage usage of each remote that reports it, keyed by the remote's
+    // ID.
+    let mut quotas: HashMap<i32, (String, RcloneAbout)> = HashMap::new();
+
+    // The remotes that were warned about being almost full, so they aren't
+    // warned about again until they go back under the threshold.
+    let mut quota_warnings: HashSet<i32> = HashSet::new();
+
+    // Try reaching offline remotes again as soon as the network comes back.
+    let network_up = Rc::new(Cell::new(false));
+    gio::NetworkMonitor::default().connect_network_changed(
+        glib::clone!(@strong network_up => move |_, available| {
+            if available {
+                network_up.set(true);
+            }
+        }),
+    );
+
     'main: loop {
+        // Apply any pause or resume requests from the tray icon.
+        let mut settings = SettingsModel::get(&db);
+
+        if let Some(paused) = (*PAUSE_REQUEST).lock().unwrap().take() {
+            let mut active_model: SettingsActiveModel = settings.into();
+            active_model.paused = ActiveValue::Set(paused);
+            settings = util::await_future(active_model.update(&db)).unwrap();
+        }
+
         // Break the loop if the user requested to quit the application.
         if *(*CLOSE_REQUEST).lock().unwrap() {
             break 'main;
@@ -1112,6 +2540,65 @@ pub fn launch(app: &Application, background: bool) {
 
         util::run_in_background(|| thread::sleep(Duration::from_millis(500)));
 
+        // The sync directories we came across, so we can stop watching the ones that
+        // got removed.
+        let mut seen_sync_dirs = HashSet::new();
+
+        // Check every remote right away if requested from the tray icon.
+        let check_all = std::mem::take(&mut *(*CHECK_REQUEST).lock().unwrap());
+        let reconnect = network_up.replace(false);
+        let remote_ids: HashSet<i32> = remotes.iter().map(|remote| remote.id).collect();
+
+        // Rclone's bandwidth limit covers all transfers at once, including ones still
+        // running for other remotes, so apply the strictest of the limits that
+        // currently apply to each remote. Remotes without a limit of their own use the
+        // global one.
+        let mut rates = vec![];
+        for remote in &remotes {
+            let (remote_id, schedule) = match &remote.bandwidth_limit {
+                Some(schedule) => (Some(remote.id), schedule.clone()),
+                None => (None, settings.bandwidth_limit.clone()),
+            };
+
+            match BandwidthSchedule::parse(&schedule) {
+                Ok(schedule) => {
+                    invalid_bandwidth_limits.remove(&remote_id);
+                    rates.push(schedule.current_rate().to_owned());
+                }
+                // Let the user know once if a stored schedule can't be used, instead of
+                // silently not limiting anything.
+                Err(err) => {
+                    if invalid_bandwidth_limits.insert(remote_id) {
+                        let notification = gio::Notification::new(&match remote_id {
+                            Some(_) => {
+                                tr::tr!("The bandwidth limit of '{}' is invalid", remote.name)
+                            }
+                            None => tr::tr!("The global bandwidth limit is invalid"),
+                        });
+                        notification.set_body(Some(&tr::tr!(
+                            "{} Nothing gets limited until it's fixed in the settings.",
+                            err
+                        )));
+                        app.send_notification(
+                            Some(&format!(
+                                "bandwidth-limit-{}",
+                                remote_id.map_or("global".to_owned(), |id| id.to_string())
+                            )),
+                            &notification,
+                        );
+                    }
+                }
+            }
+        }
+        let rate = bwlimit::strictest_rate(rates.iter().map(String::as_str));
+
+        if bandwidth_limit.as_ref() != Some(&rate)
+            && rclone::sync::set_bandwidth_limit(&rate).is_ok()
+        {
+            handle.update(|tray| tray.set_bandwidth_limit(&rate));
+            bandwidth_limit = Some(rate);
+        }
+
         for remote in remotes {
             // Process any remote deletion requests.
             {
@@ -1124,34 +2611,8 @@ pub fn launch(app: &Application, background: bool) {
                     let child = stack.child_by_name(&remote_name).unwrap();
                     stack.remove(&child);
 
-                    // Delete all related database entries.
-                    util::await_future(async {
-                        let db_remote = RemotesEntity::find()
-                            .filter(RemotesColumn::Name.eq(remote_name.clone()))
-                            .one(&db)
-                            .await
-                            .unwrap()
-                            .unwrap();
-                        let sync_dirs = SyncDirsEntity::find()
-                            .filter(SyncDirsColumn::RemoteId.eq(db_remote.id))
-                            .all(&db)
-                            .await
-                            .unwrap();
-
-                        for sync_dir in sync_dirs {
-                            SyncItemsEntity::delete_many()
-                                .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
-                                .exec(&db)
-                                .await
-                                .unwrap();
-                            sync_dir.delete(&db).await.unwrap();
-                        }
-
-                        db_remote.delete(&db).await.unwrap();
-                    });
-
-                    // Delete the Rclone config.
-                    rclone::sync::delete_config(&remote_name).unwrap();
+                    // Delete all related database entries and the Rclone config.
+                    sync::delete_remote(&db, &remote_name);
                 }
             }
 
@@ -1161,234 +2622,114 @@ pub fn launch(app: &Application, background: bool) {
                     .all(&db),
             )
             .unwrap();
+            seen_sync_dirs.extend(sync_dirs.iter().map(|sync_dir| sync_dir.id));
+            let offline = offline_remotes.contains_key(&remote.id);
+
+            // Check the remote's storage usage every so often. Not every remote reports
+            // it, in which case there's nothing to show.
+            if !offline
+                && quota_checks
+                    .get(&remote.id)
+                    .map_or(true, |checked| checked.elapsed() >= QUOTA_CHECK_INTERVAL)
+            {
+                quota_checks.insert(remote.id, Instant::now());
+                let about = rclone::sync::about(&remote.name).ok();
 
-            for sync_dir in sync_dirs {
-                // Get the list of local and remote files for this sync
-                // directory, and if they don't match the last sync state
-                // in the database, then continue with syncing.
-                let mut should_sync = false;
-
-                // Local file checks.
-                let local_glob = format!("{}/**/*", sync_dir.local_path);
-                if let Ok(paths) = glob::glob(&local_glob) {
-                    for maybe_path in paths {
-                        if let Ok(path) = maybe_path {
-                            let file = match File::open(&path) {
-                                Ok(file) => file,
-                                Err(_) => {
-                                    should_sync = true;
-                                    break;
-                                }
-                            };
-                            let current_timestamp = file
-                                .metadata()
-                                .unwrap()
-                                .modified()
-                                .unwrap()
-                                .duration_since(SystemTime::UNIX_EPOCH)
-                                .unwrap()
-                                .as_secs();
-                            let maybe_db_sync_item = util::await_future(
-                                SyncItemsEntity::find()
-                                    .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
-                                    .filter(
-                                        SyncItemsColumn::LocalPath.eq(path.display().to_string()),
-                                    )
-                                    .one(&db),
-                            )
-                            .unwrap();
-
-                            let db_timestamp: u64 = if let Some(db_sync_item) = maybe_db_sync_item {
-                                db_sync_item.last_local_timestamp.try_into().unwrap()
-                            } else {
-                                should_sync = true;
-                                break;
-                            };
-
-                            if current_timestamp != db_timestamp {
-                                should_sync = true;
-                                break;
-                            }
-                        } else {
-                            should_sync = true;
-                            break;
-                        }
-                    }
-                } else {
-                    // TODO: We should show the user an error instead of trying to sync again.
-                    should_sync = true;
+                if let Some(display) = quota_displays.get_ref().get(&remote.name) {
+                    display.update(about.as_ref());
                 }
 
-                // Remote file checks.
-                if let Ok(paths) = rclone::sync::list(
-                    &remote.name,
-                    &sync_dir.remote_path,
-                    true,
-                    RcloneListFilter::All,
-                ) {
-                    for path in paths {
-                        let stripped_path = match path.name.contains('/') {
-                            true => path
-                                .name
-                                .strip_suffix(&format!("{}/", sync_dir.remote_path))
-                                .unwrap()
-                                .to_string(),
-                            false => path.name.clone(),
-                        };
-                        let maybe_db_sync_item = util::await_future(
-                            SyncItemsEntity::find()
-                                .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
-                                .filter(SyncItemsColumn::RemotePath.eq(stripped_path))
-                                .one(&db),
-                        )
-                        .unwrap();
-                        let db_timestamp: i64 = if let Some(db_sync_item) = maybe_db_sync_item {
-                            db_sync_item.last_remote_timestamp.into()
-                        } else {
-                            should_sync = true;
-                            break;
-                        };
-                        let remote_timestamp = path.mod_time.unix_timestamp();
-
-                        if remote_timestamp != db_timestamp {
-                            should_sync = true;
-                            break;
+                // Warn once when the remote gets too full, before syncing starts failing.
+                let usage = about.as_ref().and_then(|about| about.usage());
+                let threshold = settings.quota_warning;
+
+                match usage {
+                    Some(usage) if threshold != 0 && usage * 100.0 >= threshold as f64 => {
+                        if quota_warnings.insert(remote.id) {
+                            let notification = gio::Notification::new(&tr::tr!(
+                                "'{}' is almost full",
+                                remote.name
+                            ));
+                            notification.set_body(Some(&tr::tr!(
+                                "{}% of its storage is in use. Files can't be synced to it once it's full.",
+                                (usage * 100.0).round()
+                            )));
+                            app.send_notification(
+                                Some(&format!("quota-{}", remote.id)),
+                                &notification,
+                            );
                         }
                     }
-                } else {
-                    // TODO: We should show the disconnected icon instead of trying to sync again.
-                    should_sync = true;
+                    _ => {
+                        quota_warnings.remove(&remote.id);
+                    }
                 }
 
-                // DB file checks. This covers files that got deleted locally or on the remote,
-                // as those changes wouldn't neccesarily be visible above.
-                let sync_items = util::await_future(
-                    SyncItemsEntity::find()
-                        .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
-                        .all(&db),
-                )
-                .unwrap();
-
-                for sync_item in sync_items {
-                    let remote_path = if !sync_dir.remote_path.is_empty() {
-                        format!("{}/{}", sync_dir.remote_path, sync_item.remote_path)
-                    } else {
-                        sync_item.remote_path.clone()
-                    };
-                    let maybe_remote_timestamp: Option<i32> =
-                        rclone::sync::stat(&remote.name, &remote_path)
-                            .ok()
-                            .flatten()
-                            .map(|remote_item| {
-                                remote_item.mod_time.unix_timestamp().try_into().unwrap()
-                            });
-
-                    // If the path doesn't exist both locally and on the remote, then we need to
-                    // delete the DB entry.
-                    if !Path::new(&sync_item.local_path).exists()
-                        && maybe_remote_timestamp.is_none()
-                    {
-                        util::await_future(async {
-                            SyncItemsEntity::find()
-                                .filter(SyncItemsColumn::Id.eq(sync_item.id))
-                                .one(&db)
-                                .await
-                                .unwrap()
-                                .unwrap()
-                                .delete(&db)
-                                .await
-                                .unwrap();
-                        });
-                        continue;
+                match about {
+                    Some(about) if about.summary().is_some() => {
+                        quotas.insert(remote.id, (remote.name.clone(), about));
                     }
-
-                    let remote_timestamp = match maybe_remote_timestamp {
-                        Some(timestamp) => timestamp,
-                        None => {
-                            should_sync = true;
-                            break;
-                        }
-                    };
-
-                    let local_timestamp: i32 = match fs::metadata(&sync_item.local_path) {
-                        Ok(metadata) => metadata
-                            .modified()
-                            .unwrap()
-                            .duration_since(SystemTime::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs()
-                            .try_into()
-                            .unwrap(),
-                        Err(_) => {
-                            should_sync = true;
-                            break;
-                        }
-                    };
-
-                    if local_timestamp != sync_item.last_local_timestamp
-                        || remote_timestamp != sync_item.last_remote_timestamp
-                    {
-                        should_sync = true;
-                        break;
+                    _ => {
+                        quotas.remove(&remote.id);
                     }
                 }
+            }
 
-                let item_ptr = directory_map.get_ref();
-                let item = item_ptr
-                    .get(&remote.name)
-                    .unwrap()
-                    .get(&(sync_dir.local_path.clone(), sync_dir.remote_path.clone()))
-                    .unwrap();
+            // Only check the remote once it's due, unless the user asked for it or
+            // something changed locally. Paused directories and ones with errors waiting
+            // to be resolved don't count, as they won't be synced anyway, and neither does
+            // anything while the remote is offline, as it gets retried with its own
+            // backoff.
+            let poller = pollers.entry(remote.id).or_insert_with(|| {
+                PollScheduler::new(Duration::from_secs(remote.poll_interval as u64))
+            });
+            poller.set_base_interval(Duration::from_secs(remote.poll_interval as u64));
 
-                if !should_sync {
-                    item.status_text.set_label(&tr::tr!("Files are synced."));
-                    item.status_icon
-                        .set_child(Some(&get_image("object-select-symbolic")));
-                    continue;
-                }
+            if check_all
+                || check_requests.get_mut_ref().remove(&remote.name)
+                || (reconnect && offline)
+            {
+                poller.force();
+            }
 
-                // Notify the tray app that we're syncing this remote now. We
-                // do this here instead for each remote, because the above
-                // sync dir check might make us not change anything on this remote.
-                handle.update(|tray| {
-                    tray.set_msg(tr::tr!("Syncing '{}'...", remote.name));
-                    tray.set_syncing();
-                });
+            let local_changes_found = sync_dirs.iter().any(|sync_dir| {
+                let has_errors = directory_map
+                    .get_ref()
+                    .get(&remote.name)
+                    .and_then(|dirs| {
+                        dirs.get(&(sync_dir.local_path.clone(), sync_dir.remote_path.clone()))
+                    })
+                    .is_some_and(|item| item.error_status_text.text().len() != 0);
+
+                !has_errors
+                    && !offline
+                    && !settings.paused
+                    && !sync_dir.paused
+                    && watchers.get_mut(&sync_dir.id).is_some_and(|watcher| {
+                        matches!(watcher.changes(), LocalChanges::Paths(paths) if !paths.is_empty())
+                    })
+            });
 
-                // If we have pending errors that need resolved, don't sync this directory.
-                if item.error_status_text.text().len() != 0 {
-                    continue;
-                }
+            if !poller.is_due() && !local_changes_found {
+                continue;
+            }
 
-                // Set up the UI for notifying the user that this directory is being synced.
-                // The width/height and margins for this are based on those from `get_image()`
-                // at the top of this file, as they're placed at the same place in the UI.
-                let spinner = Spinner::builder()
-                    .spinning(true)
-                    .width_request(4)
-                    .height_request(4)
-                    .margin_start(3)
-                    .margin_end(3)
-                    .build();
-                item.status_icon.set_child(Some(&spinner));
-                item.status_text
-                    .set_label(&tr::tr!("Checking for changes..."));
-                // Dropping this is important, otherwise the pointer borrow might last a lot
-                // longer and other parts of the code won't be able to get a pointer to the
-                // directory indexmap.
-                drop(item_ptr);
+            // Whether any directory on this remote had changes to sync.
+            let mut changes_found = false;
+            // The error from Rclone if the remote turned out to be unreachable.
+            let disconnected: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
 
+            for sync_dir in sync_dirs {
                 // Add an error for reporting in the UI.
                 let please_resolve_msg_tr = tr::tr!("Please resolve the reported syncing issues.");
                 let please_resolve_msg = " ".to_owned() + &please_resolve_msg_tr;
-                let add_error = glib::clone!(@strong db, @strong directory_map, @strong remote, @strong sync_dir, @strong sync_errors_count, @strong please_resolve_msg, @strong handle => move |error: SyncError| {
+                let add_error = glib::clone!(@strong db, @strong directory_map, @strong remote, @strong sync_dir, @strong sync_errors_count, @strong please_resolve_msg, @strong handle, @strong confirmed_deletions, @strong check_requests, @strong reconnect_offers, @weak app => move |error: SyncError| {
                     let path_pair = (sync_dir.local_path.clone(), sync_dir.remote_path.clone());
                     let ui_item = error.generate_ui();
                     let ui_item_listbox = ListBoxRow::builder().child(&ui_item).build();
 
                     // Generate the callback.
-                    let gesture = GestureClick::new();
-                    gesture.connect_released(glib::clone!(@strong directory_map, @strong remote, @strong path_pair, @strong db, @strong error, @weak ui_item, @weak ui_item_listbox, @strong please_resolve_msg => move |_, _, _, _| {
+                    let show_fix_dialog = glib::clone!(@strong directory_map, @strong remote, @strong sync_dir, @strong path_pair, @strong db, @strong error, @weak ui_item, @weak ui_item_listbox, @strong please_resolve_msg, @strong confirmed_deletions, @strong check_requests, @strong reconnect_offers, @weak app => move || {
                         ui_item.set_sensitive(false);
                         let remove_ui_item = glib::clone!(@strong directory_map, @strong remote, @strong path_pair, @strong error, @weak ui_item_listbox, @strong please_resolve_msg => move || {
                             let mut ptr = directory_map.get_mut_ref();
@@ -1418,21 +2759,50 @@ pub fn launch(app: &Application, background: bool) {
                         });
 
                         match &error {
-                            SyncError::General(_, _) => {
+                            SyncError::General(_, kind, err) => {
                                 let dialog = MessageDialog::builder()
-                                    .text(&tr::tr!("Would you like to dismiss this error?"))
-                                    .buttons(ButtonsType::YesNo)
+                                    .text(&kind.message())
+                                    .secondary_text(&format!("{}\n\n{}", kind.suggestion(), err))
                                     .build();
+
+                                // Errors that can go away on their own or that we don't know anything about can be retried right away, the rest have to be fixed first.
+                                let needs_login = matches!(kind, RcloneErrorKind::AuthExpired | RcloneErrorKind::TwoFactorRequired);
+                                if kind.is_retryable() || *kind == RcloneErrorKind::Other {
+                                    dialog.add_button(&tr::tr!("Retry"), ResponseType::Other(0));
+                                }
+                                if needs_login {
+                                    dialog.add_button(&tr::tr!("Reconnect"), ResponseType::Other(2));
+                                }
+                                dialog.add_button(&tr::tr!("Dismiss"), ResponseType::Other(1));
+                                dialog.add_button(&tr::tr!("Cancel"), ResponseType::Cancel);
                                 dialog.connect_close_request(glib::clone!(@strong ui_item => move |_| {
                                     ui_item.set_sensitive(true);
                                     Inhibit(false)
                                 }));
-                                dialog.connect_response(glib::clone!(@strong directory_map, @strong remote, @strong path_pair, @weak ui_item, @strong error, @strong remove_ui_item => move |dialog, resp| {
+                                dialog.connect_response(glib::clone!(@strong remote, @weak ui_item, @strong remove_ui_item, @strong check_requests, @strong reconnect_offers, @weak app => move |dialog, resp| {
                                     match resp {
-                                        ResponseType::Yes => {
+                                        ResponseType::Other(0) => {
+                                            remove_ui_item();
+                                            check_requests.get_mut_ref().insert(remote.name.clone());
+                                        },
+                                        // Log in again, keeping the remote's config and sync history.
+                                        ResponseType::Other(2) => {
+                                            dialog.close();
+
+                                            if login::reconnect(&app, &remote.name) {
+                                                reconnect_offers.get_mut_ref().remove(&remote.name);
+                                                remove_ui_item();
+                                                check_requests.get_mut_ref().insert(remote.name.clone());
+                                            } else {
+                                                ui_item.set_sensitive(true);
+                                            }
+
+                                            return;
+                                        },
+                                        ResponseType::Other(1) => {
                                             remove_ui_item();
                                         },
-                                        ResponseType::No => {
+                                        ResponseType::Cancel => {
                                             ui_item.set_sensitive(true);
                                         },
                                         _ => return,
@@ -1445,37 +2815,26 @@ pub fn launch(app: &Application, background: bool) {
                             SyncError::BothMoreCurrent(local_item, remote_item) => {
                                 let local_item_formatted = util::fmt_home(local_item);
                                 let local_path = Path::new(&local_item);
-                                let sync_local_to_remote = glib::clone!(@strong remote, @strong local_item_formatted, @strong local_item, @strong remote_item => move || {
-                                    if let Err(err) = rclone::sync::copy_to_remote(&local_item, &remote.name, &remote_item) {
-                                        gtk_util::show_error(&tr::tr!("Failed to sync '{}' to '{}' on remote.", local_item_formatted, remote_item), Some(&err.error));
-                                        Err(())
-                                    } else {
-                                        Ok(())
-                                    }
-                                });
-                                let sync_remote_to_local = glib::clone!(@strong remote, @strong local_item_formatted, @strong local_item, @strong remote_item => move || {
-                                    if let Err(err) = rclone::sync::copy_to_local(&local_item, &remote.name, &remote_item) {
-                                        gtk_util::show_error(&tr::tr!("Failed to sync '{}' on remote to '{}'.", remote_item, local_item_formatted), Some(&err.error));
+                                let engine = Rc::new(SyncEngine::new(
+                                    db.clone(),
+                                    remote.clone(),
+                                    sync_dir.clone(),
+                                    Arc::new(Mutex::new(false)),
+                                    boxed::Box::new(|_| ()),
+                                ));
+                                let resolve_conflict = glib::clone!(@strong engine, @strong local_item_formatted, @strong local_item, @strong remote_item => move |resolution: ConflictResolution| {
+                                    if let Err(err) = engine.resolve_conflict(&local_item, &remote_item, resolution) {
+                                        let err_msg = match resolution {
+                                            ConflictResolution::Local => tr::tr!("Failed to sync '{}' to '{}' on remote.", local_item_formatted, remote_item),
+                                            ConflictResolution::Remote => tr::tr!("Failed to sync '{}' on remote to '{}'.", remote_item, local_item_formatted),
+                                            ConflictResolution::KeepBoth => tr::tr!("Failed to keep both '{}' and '{}' on remote.", local_item_formatted, remote_item),
+                                        };
+                                        gtk_util::show_error(&err_msg, Some(&err.message()));
                                         Err(())
                                     } else {
                                         Ok(())
                                     }
                                 });
-                                let local_item = local_item.clone();
-                                let update_db_item = glib::clone!(@strong db, @strong remote, @strong local_item, @strong remote_item => move || {
-                                    let local_timestamp = Path::new(&local_item).metadata().unwrap().modified().unwrap().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
-                                    let remote_timestamp = rclone::sync::stat(&remote.name, &remote_item).unwrap().unwrap().mod_time.unix_timestamp();
-                                    let mut active_model: SyncItemsActiveModel = util::await_future(SyncItemsEntity::find()
-                                        .filter(SyncItemsColumn::LocalPath.eq(local_item.clone()))
-                                        .filter(SyncItemsColumn::RemotePath.eq(remote_item.clone()))
-                                        .one(&db)
-                                    ).unwrap()
-                                    .unwrap()
-                                    .into();
-                                    active_model.last_local_timestamp = ActiveValue::set(local_timestamp.try_into().unwrap());
-                                    active_model.last_remote_timestamp = ActiveValue::Set(remote_timestamp.try_into().unwrap());
-                                    util::await_future(active_model.update(&db)).unwrap();
-                                });
                                 let rclone_remote_item = match rclone::sync::stat(&remote.name, remote_item) {
                                     Ok(item) => item,
                                     Err(err) => {
@@ -1495,16 +2854,14 @@ pub fn launch(app: &Application, background: bool) {
                                 // Otherwise if only the local exists, use that.
                                 } else if local_path.exists() && rclone_remote_item.is_none() {
                                     gtk_util::show_error(&tr::tr!("File Update"), Some(&tr::tr!("Only the local item exists now, so it will be synced to the remote.")));
-                                    if sync_local_to_remote().is_ok() {
-                                        update_db_item();
+                                    if resolve_conflict(ConflictResolution::Local).is_ok() {
                                         remove_ui_item();
                                         return;
                                     }
                                 // Otherwise if only the remote exists, use that.
                                 } else if !local_path.exists() && rclone_remote_item.is_some() {
                                     gtk_util::show_error(&tr::tr!("File Update"), Some(&tr::tr!("Only the remote item exists now, so it will be synced to the local machine.")));
-                                    if sync_remote_to_local().is_ok() {
-                                        update_db_item();
+                                    if resolve_conflict(ConflictResolution::Remote).is_ok() {
                                         remove_ui_item();
                                         return;
                                     }
@@ -1514,39 +2871,79 @@ pub fn launch(app: &Application, background: bool) {
                                     .text(
                                         &tr::tr!("Both the local item '{}' and remote item '{}' have been updated since the last sync.", local_item_formatted, remote_item)
                                     )
-                                    .secondary_text(&tr::tr!("Which item would you like to keep?"))
+                                    .secondary_text(&tr::tr!("Which item would you like to keep? Keeping both renames the local item to a conflicted copy."))
                                     .build();
                                 dialog.add_button(&tr::tr!("Local"), ResponseType::Other(0));
                                 dialog.add_button(&tr::tr!("Remote"), ResponseType::Other(1));
+                                dialog.add_button(&tr::tr!("Keep Both"), ResponseType::Other(2));
                                 dialog.connect_close_request(glib::clone!(@strong ui_item => move |_| {
                                     ui_item.set_sensitive(true);
                                     Inhibit(false)
                                 }));
-                                dialog.connect_response(glib::clone!(@strong directory_map, @strong remote, @strong path_pair, @weak ui_item, @strong error, @strong local_item, @strong remote_item, @strong local_path, @strong rclone_remote_item, @strong sync_local_to_remote, @strong sync_remote_to_local => move |dialog, resp| {
+                                dialog.connect_response(glib::clone!(@strong remove_ui_item, @strong resolve_conflict => move |dialog, resp| {
+                                    let resolution = match resp {
+                                        ResponseType::Other(0) => ConflictResolution::Local,
+                                        ResponseType::Other(1) => ConflictResolution::Remote,
+                                        ResponseType::Other(2) => ConflictResolution::KeepBoth,
+                                        ResponseType::Other(_) => unreachable!(),
+                                        _ => return
+                                    };
+
+                                    if resolve_conflict(resolution).is_ok() {
+                                        remove_ui_item();
+                                    }
+
+                                    dialog.close();
+                                }));
+
+                                dialog.show();
+                            }
+                            SyncError::MassDeletion(_, _) => {
+                                let dialog = MessageDialog::builder()
+                                    .text(&error.message())
+                                    .secondary_text(&tr::tr!("This can happen when items get removed by accident, or when a folder gets renamed or unmounted. Would you like to delete the items on the other side too, or copy them back?"))
+                                    .build();
+                                dialog.add_button(&tr::tr!("Delete"), ResponseType::Other(0));
+                                dialog.add_button(&tr::tr!("Restore"), ResponseType::Other(1));
+                                dialog.connect_close_request(glib::clone!(@strong ui_item => move |_| {
+                                    ui_item.set_sensitive(true);
+                                    Inhibit(false)
+                                }));
+                                dialog.connect_response(glib::clone!(@strong db, @strong remote, @strong sync_dir, @strong confirmed_deletions, @strong remove_ui_item => move |dialog, resp| {
                                     match resp {
+                                        // Let the next sync go through with the deletions.
                                         ResponseType::Other(0) => {
-                                            if sync_local_to_remote().is_ok() {
-                                                update_db_item();
-                                                remove_ui_item();
-                                            }
-                                        },
+                                            confirmed_deletions.get_mut_ref().insert(sync_dir.id);
+                                        }
                                         ResponseType::Other(1) => {
-                                            if sync_remote_to_local().is_ok() {
-                                                update_db_item();
-                                                remove_ui_item();
+                                            let engine = SyncEngine::new(
+                                                db.clone(),
+                                                remote.clone(),
+                                                sync_dir.clone(),
+                                                Arc::new(Mutex::new(false)),
+                                                boxed::Box::new(|_| ()),
+                                            );
+
+                                            if let Err(err) = engine.restore_deletions() {
+                                                gtk_util::show_error(&tr::tr!("Failed to restore the deleted items."), Some(&err.message()));
+                                                dialog.close();
+                                                return;
                                             }
-                                        },
+                                        }
                                         ResponseType::Other(_) => unreachable!(),
                                         _ => return
                                     }
 
+                                    remove_ui_item();
                                     dialog.close();
                                 }));
 
                                 dialog.show();
                             }
                         }
-                    }));
+                    });
+                    let gesture = GestureClick::new();
+                    gesture.connect_released(glib::clone!(@strong show_fix_dialog => move |_, _, _, _| show_fix_dialog()));
                     ui_item.add_controller(&gesture);
 
                     // If we have zero errors now, remove the warning icon.
@@ -1573,20 +2970,20 @@ pub fn launch(app: &Application, background: bool) {
                     item.error_status_text.set_label(&(error_string + " "));
 
                     // Add the error to the UI.
+                    let needs_login = matches!(error, SyncError::General(_, RcloneErrorKind::AuthExpired | RcloneErrorKind::TwoFactorRequired, _));
                     item.error_list.append(&ui_item_listbox);
                     item.error_items.insert(error, ui_item);
                     (item.update_error_ui)();
+                    drop(ptr);
 
                     // Set the tray icon to show the warning icon.
                     handle.update(|tray| tray.set_warning());
-                });
 
-                // A vector of local/remote sync item pairs to make sure we don't sync anything
-                // twice between 'sync_local_directory' and 'sync_remote_directory' below. It
-                // also prevents errors from showing up twice when they occur. We have to wrap
-                // this in a [`RefCell`] to avoid some borrow checker issues with multiple
-                // mutable closures needing access to this.
-                let synced_items: RefCell<Vec<(String, String)>> = RefCell::new(vec![]);
+                    // Offer to log in again right away if the remote's login expired, once per remote.
+                    if needs_login && reconnect_offers.get_mut_ref().insert(remote.name.clone()) {
+                        show_fix_dialog();
+                    }
+                });
 
                 // Get any pending deletion requests and process them.
                 let process_deletion_requests = glib::clone!(@strong db, @weak stack, @strong directory_map, @strong remote_deletion_queue, @strong sync_dir_deletion_queue => move || {
@@ -1607,22 +3004,7 @@ pub fn launch(app: &Application, background: bool) {
                         dmap.get_mut(&queue_item.0).unwrap().remove(&dir_pair).unwrap();
 
                         // Remove the item from the database.
-                        util::await_future(async {
-                            let sync_dir = SyncDirsEntity::find()
-                                .filter(SyncDirsColumn::LocalPath.eq(queue_item.1.clone()))
-                                .filter(SyncDirsColumn::RemotePath.eq(queue_item.2.clone()))
-                                .one(&db)
-                                .await
-                                .unwrap()
-                                .unwrap();
-
-                            SyncItemsEntity::delete_many()
-                                .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
-                                .exec(&db)
-                                .await
-                                .unwrap();
-                            sync_dir.delete(&db).await.unwrap();
-                        });
+                        sync::delete_sync_dir(&db, &queue_item.1, &queue_item.2);
                     }
 
                     // Process remote deletions.
@@ -1633,940 +3015,198 @@ pub fn launch(app: &Application, background: bool) {
                         let child = stack.child_by_name(&remote_name).unwrap();
                         stack.remove(&child);
 
-                        // Delete all related database entries.
-                        util::await_future(async {
-                            let db_remote = RemotesEntity::find()
-                                .filter(RemotesColumn::Name.eq(remote_name.clone()))
-                                .one(&db)
-                                .await
-                                .unwrap()
-                                .unwrap();
-                            let sync_dirs = SyncDirsEntity::find()
-                                .filter(SyncDirsColumn::RemoteId.eq(db_remote.id))
-                                .all(&db)
-                                .await
-                                .unwrap();
-
-                            for sync_dir in sync_dirs {
-                                SyncItemsEntity::delete_many()
-                                    .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
-                                    .exec(&db)
-                                    .await
-                                    .unwrap();
-                                sync_dir.delete(&db).await.unwrap();
-                            }
-
-                            db_remote.delete(&db).await.unwrap();
-                        });
-
-                        // Delete the Rclone config.
-                        rclone::sync::delete_config(&remote_name).unwrap();
+                        // Delete all related database entries and the Rclone config.
+                        sync::delete_remote(&db, &remote_name);
                     }
                 });
 
-                // Sync a local directory. This is implemented as a function instead of a
-                // closure so that it can be called recursively.
-                //
-                // Returning an [`Err<()>`] means we this directory has to stop being synced
-                // because it was in the deletion queue. Any other error should return an
-                // [`Ok<()>`].
-                #[allow(clippy::too_many_arguments)]
-                fn sync_local_directory<
-                    F1: Fn(SyncError) + Clone,
-                    F2: Fn() + Clone,
-                    F3: Fn() + Clone,
-                >(
-                    local_dir: &Path,
-                    remote: &RemotesModel,
-                    sync_dir: &SyncDirsModel,
-                    db: &DatabaseConnection,
-                    directory_map: &DirectoryMap,
-                    synced_items: &RefCell<Vec<(String, String)>>,
-                    add_error: F1,
-                    check_open_requests: F2,
-                    process_deletion_requests: F3,
-                ) {
-                    process_deletion_requests();
-
-                    let dir_string = local_dir.to_str().unwrap().to_owned();
-                    let update_ui_progress = |dir: &str| {
-                        // If this directory no longer exists in the database (i.e. from being
-                        // deleted from the `sync_dir_deletion_queue`), then do nothing.
-                        if !sync_dir.exists(db) {
-                            return;
-                        }
-
-                        let ptr = directory_map.get_ref();
-                        let dir_pair = (sync_dir.local_path.clone(), sync_dir.remote_path.clone());
-                        let item = ptr.get(&remote.name).unwrap().get(&dir_pair).unwrap();
-                        let status_string =
-                            tr::tr!("Checking '{}' for changes...", util::fmt_home(dir));
-                        item.status_text.set_label(&status_string);
-                    };
-                    update_ui_progress(&dir_string);
-                    let directory = match fs::read_dir(local_dir) {
-                        Ok(ok_dir) => ok_dir,
-                        Err(err) => {
-                            add_error(SyncError::General(dir_string, err.to_string()));
-                            return;
-                        }
-                    };
+                // The root found missing by the sync engine, if any.
+                let missing_root: Rc<Cell<Option<SyncRoot>>> = Rc::new(Cell::new(None));
+                // The progress of the file transfers started so far.
+                let transfer_progress: Rc<RefCell<TransferProgress>> =
+                    Rc::new(RefCell::new(TransferProgress::default()));
 
-                    // Get the list of ignore globs.
-                    let ignore_file_string =
-                        format!("{}/{}", sync_dir.local_path, FILE_IGNORE_NAME);
-                    let ignore_file_path = Path::new(&ignore_file_string);
-                    let ignore_globs = if ignore_file_path.exists() {
-                        let _lock = FileLock::lock(
-                            &ignore_file_string,
-                            true,
-                            FileOptions::new().write(true).read(true),
-                        )
-                        .unwrap();
-                        let file_content = fs::read_to_string(ignore_file_path).unwrap();
-                        let mut globs = vec![];
+                // Report the sync engine's progress in the UI.
+                let on_event = glib::clone!(@strong directory_map, @strong remote, @strong sync_dir, @strong add_error, @strong check_open_requests, @strong process_deletion_requests, @strong missing_root, @strong transfer_progress, @strong disconnected, @strong handle => move |event: SyncEvent| {
+                    // Keep showing how far along the transfers running in the background are.
+                    let with_progress = |status: String| {
+                        let progress = transfer_progress.get_ref();
 
-                        for line in file_content.lines() {
-                            if let Ok(pattern) = glob::Pattern::new(line) {
-                                globs.push(pattern);
-                            }
+                        if progress.finished < progress.total {
+                            format!("{status} ({})", transfer_progress_label(&progress))
+                        } else {
+                            status
                         }
-
-                        globs
-                    } else {
-                        vec![]
                     };
-
-                    for item in directory {
-                        // If a close request was sent in, stop syncing this remote so we can quit
-                        // the application in the 'main loop.
-                        if *(*CLOSE_REQUEST).lock().unwrap() {
-                            break;
-                        }
-
-                        // Check for open requests.
-                        check_open_requests();
-
-                        // If this directory no longer exists in the database (i.e. from being
-                        // deleted from the `sync_dir_deletion_queue`), stop processing and return.
-                        if !sync_dir.exists(db) {
-                            break;
-                        }
-
-                        if let Err(err) = item {
-                            add_error(SyncError::General(dir_string.clone(), err.to_string()));
-                            continue;
-                        }
-                        let item = item.unwrap();
-                        let local_path = item.path().to_str().unwrap().to_owned();
-
-                        // The path from the root of the remote.
-                        let remote_path = {
-                            let local_path_stripped = local_path
-                                .strip_prefix(&format!("{}/", sync_dir.local_path))
-                                .unwrap();
-                            let stripped_path = match local_path_stripped.strip_suffix('/') {
-                                Some(string) => string,
-                                None => local_path_stripped,
-                            };
-
-                            if sync_dir.remote_path.is_empty() {
-                                stripped_path.to_owned()
-                            } else {
-                                sync_dir.remote_path.clone() + "/" + stripped_path
-                            }
-                        };
-                        // The above path, with `sync_dir.remote_path` stripped from it.
-                        let stripped_remote_path =
-                            if remote_path.contains('/') && sync_dir.remote_path.contains('/') {
-                                remote_path
-                                    .strip_prefix(&format!("{}/", sync_dir.remote_path))
-                                    .unwrap()
-                                    .to_owned()
-                            } else {
-                                remote_path.clone()
-                            };
-
-                        update_ui_progress(&local_path);
-                        // If this item matches the ignore list, don't sync it.
-                        if ignore_globs
-                            .iter()
-                            .filter(|pattern| pattern.matches(&stripped_remote_path))
-                            .count()
-                            > 0
-                        {
-                            continue;
-                        }
-
-                        synced_items
-                            .borrow_mut()
-                            .push((local_path.clone(), remote_path.clone()));
-
-                        let get_local_file_timestamp = || {
-                            item.metadata()
-                                .unwrap()
-                                .modified()
-                                .unwrap()
-                                .duration_since(SystemTime::UNIX_EPOCH)
-                                .unwrap()
-                                .as_secs()
-                        };
-                        let local_utc_timestamp = get_local_file_timestamp();
-                        let remote_item = match rclone::sync::stat(&remote.name, &remote_path) {
-                            Ok(item) => item,
-                            Err(err) => {
-                                add_error(SyncError::General(remote_path.clone(), err.error));
-                                continue;
-                            }
-                        };
-                        let remote_utc_timestamp = remote_item
-                            .as_ref()
-                            .map(|item| item.mod_time.unix_timestamp());
-                        let db_item = util::await_future(
-                            SyncItemsEntity::find()
-                                .filter(SyncItemsColumn::LocalPath.eq(local_path.clone()))
-                                .filter(SyncItemsColumn::RemotePath.eq(remote_path.clone()))
-                                .one(db),
-                        )
-                        .unwrap();
-
-                        // Push the item to the remote. Returns the
-                        // [`crate::rclone::sync::RcloneRemoteItem`] of the item on the remote, or
-                        // an [`Err<()>`] if an issue occurred (all errors are automatically added
-                        // via `add_errors`).
-                        let push_local_to_remote = || -> Result<rclone::RcloneRemoteItem, ()> {
-                            let file_type = item.file_type().unwrap();
-
-                            if let Some(rclone_item) = &remote_item {
-                                let same_type = file_type.is_dir() && rclone_item.is_dir;
-
-                                if !same_type {
-                                    if let Err(err) =
-                                        rclone::sync::purge(&remote.name, &remote_path)
-                                    {
-                                        add_error(SyncError::General(
-                                            remote_path.clone(),
-                                            err.error,
-                                        ));
-                                        return Err(());
-                                    }
-                                }
-                            }
-
-                            if file_type.is_dir() {
-                                if let Err(err) = rclone::sync::mkdir(&remote.name, &remote_path) {
-                                    add_error(SyncError::General(remote_path.clone(), err.error));
-                                    return Err(());
-                                }
-                                sync_local_directory(
-                                    &item.path(),
-                                    remote,
-                                    sync_dir,
-                                    db,
-                                    directory_map,
-                                    synced_items,
-                                    add_error.clone(),
-                                    check_open_requests.clone(),
-                                    process_deletion_requests.clone(),
-                                );
-                                update_ui_progress(&local_path);
-                            } else if let Err(err) = rclone::sync::copy_to_remote(
-                                &local_path,
-                                &remote.name,
-                                &remote_path,
-                            ) {
-                                add_error(SyncError::General(local_path.clone(), err.error));
-                                return Err(());
-                            }
-
-                            Ok(rclone::sync::stat(&remote.name, &remote_path)
-                                .unwrap()
-                                .unwrap())
-                        };
-                        // Pull the item from the remote.
-                        let pull_remote_to_local = || -> Result<(), ()> {
-                            let file_type = item.file_type().unwrap();
-                            let same_type =
-                                file_type.is_dir() && remote_item.as_ref().unwrap().is_dir;
-
-                            if !same_type {
-                                if file_type.is_dir()
-                                    && let Err(err) = fs::remove_dir_all(item.path())
-                                {
-                                    add_error(SyncError::General(
-                                        local_path.clone(),
-                                        err.to_string(),
-                                    ));
-                                    return Err(());
-                                } else if let Err(err) = fs::remove_file(item.path()) {
-                                    add_error(SyncError::General(
-                                        local_path.clone(),
-                                        err.to_string(),
-                                    ));
-                                    return Err(());
-                                }
-                            }
-
-                            if file_type.is_dir() {
-                                sync_local_directory(
-                                    &item.path(),
-                                    remote,
-                                    sync_dir,
-                                    db,
-                                    directory_map,
-                                    synced_items,
-                                    add_error.clone(),
-                                    check_open_requests.clone(),
-                                    process_deletion_requests.clone(),
-                                );
-                                update_ui_progress(&local_path);
-                            } else if let Err(err) =
-                                rclone::sync::copy_to_local(&local_path, &remote.name, &remote_path)
-                            {
-                                add_error(SyncError::General(remote_path.clone(), err.error));
-                                return Err(());
-                            }
-
-                            Ok(())
-                        };
-                        // Delete this item from the database.
-                        let delete_db_entry = || {
-                            util::await_future(async {
-                                SyncItemsEntity::find()
-                                    .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
-                                    .filter(SyncItemsColumn::LocalPath.eq(local_path.clone()))
-                                    .filter(SyncItemsColumn::RemotePath.eq(remote_path.clone()))
-                                    .one(db)
-                                    .await
-                                    .unwrap()
-                                    .unwrap()
-                                    .delete(db)
-                                    .await
-                                    .unwrap()
-                            })
-                        };
-
-                        // If we have a record of the last sync, use that to aid in timestamp
-                        // checks.
-                        if let Some(db_model) = db_item {
-                            let update_db_item = |local_timestamp, remote_timestamp| {
-                                let mut active_model: SyncItemsActiveModel =
-                                    db_model.clone().into();
-                                active_model.last_local_timestamp =
-                                    ActiveValue::Set(local_timestamp);
-                                active_model.last_remote_timestamp =
-                                    ActiveValue::Set(remote_timestamp);
-                                util::await_future(active_model.update(db)).unwrap();
-                            };
-
-                            // Both items are more current than at the last transaction - we need to
-                            // let the user decide which to keep.
-                            // Since `db_model.last_sync_timestamp` is an `i32`, we should be able
-                            // to safely convert it to an `i64` and `u64`.
-                            if local_utc_timestamp > db_model.last_local_timestamp as u64
-                                && let Some(remote_timestamp) = remote_utc_timestamp
-                                && remote_timestamp > db_model.last_remote_timestamp as i64
-                            {
-                                // Only add the error if one of the items is not a directory -
-                                // there's no point in saying both directories are more current, and
-                                // it's probably because one of the items in the directory got
-                                // updated anyway.
-                                if let Some(r_item) = remote_item
-                                    && (!item.path().is_dir() || !r_item.is_dir)
-                                {
-                                    add_error(SyncError::BothMoreCurrent(
-                                        local_path.clone(),
-                                        remote_path.clone(),
-                                    ));
-                                }
-                            // The local item is more recent.
-                            } else if local_utc_timestamp > db_model.last_local_timestamp as u64 {
-                                if let Ok(rclone_item) = push_local_to_remote() {
-                                    update_db_item(
-                                        get_local_file_timestamp().try_into().unwrap(),
-                                        rclone_item.mod_time.unix_timestamp().try_into().unwrap(),
-                                    );
-                                    continue;
-                                } else {
-                                    continue;
-                                }
-                            // The remote item is more recent.
-                            } else if let Some(remote_timestamp) = remote_utc_timestamp
-                                && remote_timestamp > db_model.last_remote_timestamp as i64
-                            {
-                                if pull_remote_to_local().is_err() {
-                                    continue;
-                                } else {
-                                    update_db_item(
-                                        get_local_file_timestamp().try_into().unwrap(),
-                                        remote_timestamp.try_into().unwrap(),
-                                    );
-                                }
-                            // The item is missing from the remote, but the last
-                            // recorded timestamp for the local item is still
-                            // the same. This means the item got deleted on the
-                            // server, and we need to reflect such locally.
-                            } else if remote_item.is_none()
-                                && local_utc_timestamp == db_model.last_local_timestamp as u64
-                            {
-                                if item.path().is_dir() {
-                                    if let Err(err) = fs::remove_dir_all(&local_path) {
-                                        add_error(SyncError::General(
-                                            local_path.clone(),
-                                            err.to_string(),
-                                        ));
-                                        continue;
-                                    }
-                                } else if let Err(err) = fs::remove_file(&local_path) {
-                                    add_error(SyncError::General(
-                                        local_path.clone(),
-                                        err.to_string(),
-                                    ));
-                                    continue;
-                                }
-
-                                delete_db_entry();
-                                continue;
-                            // Both the local and remote item remain unchanged -
-                            // do nothing.
-                            } else if local_utc_timestamp == db_model.last_local_timestamp as u64
-                                && let Some(remote_timestamp) = remote_utc_timestamp
-                                && remote_timestamp == db_model.last_remote_timestamp as i64
-                            {
-                                continue;
-                            // Every possible scenario should have been covered
-                            // above, so panic if not.
-                            } else {
-                                unreachable!();
-                            }
-                        // Otherwise just check the local timestamps against
-                        // those on the remote, and record our new transaction
-                        // in the database.
-                        } else {
-                            // If the timestamp exists, then the remote item did, so check
-                            // timestamps.
-                            if let Some(remote_timestamp) = remote_utc_timestamp {
-                                if local_utc_timestamp > remote_timestamp as u64 {
-                                    if push_local_to_remote().is_err() {
-                                        continue;
-                                    }
-                                } else if pull_remote_to_local().is_err() {
-                                    continue;
-                                }
-                            // Otherwise the remote item didn't exist, so just
-                            // sync our local copy.
-                            } else if push_local_to_remote().is_err() {
-                                continue;
+                    let status_string = match event {
+                        SyncEvent::CheckingLocal(path) => with_progress(tr::tr!("Checking '{}' for changes...", util::fmt_home(&path))),
+                        SyncEvent::CheckingRemote(path) => with_progress(tr::tr!("Checking '{}' on remote for changes...", path)),
+                        SyncEvent::Transferring(progress) => {
+                            let dir_pair = (sync_dir.local_path.clone(), sync_dir.remote_path.clone());
+                            if let Some(item) = directory_map.get_mut_ref().get_mut(&remote.name).and_then(|dirs| dirs.get_mut(&dir_pair)) {
+                                item.update_transfers(&progress.files);
                             }
 
-                            // The remote item is now guaranteed to exist, so fetch it.
-                            let remote_item_safe =
-                                match rclone::sync::stat(&remote.name, &remote_path) {
-                                    Ok(item) => item.unwrap(),
-                                    Err(err) => {
-                                        add_error(SyncError::General(
-                                            remote_path.clone(),
-                                            err.error,
-                                        ));
-                                        continue;
-                                    }
-                                };
-                            match rclone::sync::stat(&remote.name, &remote_path) {
-                                Ok(item) => item.unwrap(),
-                                Err(err) => {
-                                    add_error(SyncError::General(remote_path.clone(), err.error));
-                                    continue;
-                                }
-                            };
-
-                            // Record the current transaction's timestamps in the database.
-                            util::await_future(
-                                SyncItemsActiveModel {
-                                    sync_dir_id: ActiveValue::Set(sync_dir.id),
-                                    local_path: ActiveValue::Set(local_path.clone()),
-                                    remote_path: ActiveValue::Set(remote_path.clone()),
-                                    last_local_timestamp: ActiveValue::Set(
-                                        local_utc_timestamp.try_into().unwrap(),
-                                    ),
-                                    last_remote_timestamp: ActiveValue::Set(
-                                        remote_item_safe
-                                            .mod_time
-                                            .unix_timestamp()
-                                            .try_into()
-                                            .unwrap(),
-                                    ),
-                                    ..Default::default()
-                                }
-                                .insert(db),
-                            )
-                            .unwrap();
+                            let file_labels: Vec<String> = progress.files.iter().map(file_progress_label).collect();
+                            let status = [transfer_progress_label(&progress)].into_iter().chain(file_labels.clone()).collect::<Vec<String>>().join(" · ");
+                            handle.update(|tray| tray.set_transfers(file_labels));
+                            *transfer_progress.get_mut_ref() = progress;
+                            status
                         }
-                    }
-                }
-
-                // Sync a remote directory. It's implemented as a function because of the same
-                // logic for `fn sync_local_directory` above.
-                // - NOTE: `remote_dir` should be: 1. the path with any `/` prefix/suffix
-                //   removed 2. the full path from the root of the remote server.
-                #[allow(clippy::too_many_arguments)]
-                fn sync_remote_directory<
-                    F1: Fn(SyncError) + Clone,
-                    F2: Fn() + Clone,
-                    F3: Fn() + Clone,
-                >(
-                    remote_dir: &str,
-                    remote: &RemotesModel,
-                    sync_dir: &SyncDirsModel,
-                    db: &DatabaseConnection,
-                    directory_map: &DirectoryMap,
-                    synced_items: &RefCell<Vec<(String, String)>>,
-                    add_error: F1,
-                    check_open_requests: F2,
-                    process_deletion_requests: F3,
-                ) {
-                    process_deletion_requests();
-
-                    let ignore_file_string =
-                        format!("{}/{}", sync_dir.local_path, FILE_IGNORE_NAME);
-                    let ignore_file_path = Path::new(&ignore_file_string);
-                    let ignore_globs = if ignore_file_path.exists() {
-                        let _lock = FileLock::lock(
-                            ignore_file_path,
-                            true,
-                            FileOptions::new().write(true).read(true),
-                        )
-                        .unwrap();
-                        let file_content = fs::read_to_string(ignore_file_path).unwrap();
-                        let mut globs = vec![];
-
-                        for line in file_content.lines() {
-                            if let Ok(pattern) = glob::Pattern::new(line) {
-                                globs.push(pattern);
-                            }
+                        SyncEvent::Error(error) => {
+                            add_error(error);
+                            return;
                         }
-
-                        globs
-                    } else {
-                        vec![]
-                    };
-                    let update_ui_progress = |dir: &str| {
-                        // If this directory no longer exists in the database (i.e. from being
-                        // deleted from the `sync_dir_deletion_queue`, do nothing).
-                        if !sync_dir.exists(db) {
+                        SyncEvent::RootMissing(root) => {
+                            missing_root.set(Some(root));
                             return;
                         }
-
-                        let ptr = directory_map.get_ref();
-                        let dir_pair = (sync_dir.local_path.clone(), sync_dir.remote_path.clone());
-                        let item = ptr.get(&remote.name).unwrap().get(&dir_pair).unwrap();
-                        let status_string = tr::tr!("Checking '{}' on remote for changes...", dir);
-                        item.status_text.set_label(&status_string);
-                    };
-                    update_ui_progress(remote_dir);
-                    let items = match rclone::sync::list(
-                        &remote.name,
-                        remote_dir,
-                        false,
-                        RcloneListFilter::All,
-                    ) {
-                        Ok(ok_items) => ok_items,
-                        Err(err) => {
-                            add_error(SyncError::General(remote_dir.to_owned(), err.error));
+                        SyncEvent::Disconnected(err) => {
+                            *disconnected.get_mut_ref() = Some(err);
                             return;
                         }
                     };
 
-                    for item in items {
-                        // If a close request was sent in, stop syncing this remote so we can quit
-                        // the application in the 'main loop.
-                        if *(*CLOSE_REQUEST).lock().unwrap() {
-                            break;
-                        }
-
-                        // Check for open requests.
-                        check_open_requests();
-
-                        // If this directory no longer exists in the database (i.e. from being
-                        // deleted from the `sync_dir_deletion_queue`), stop processing and return.
-                        if !sync_dir.exists(db) {
-                            break;
-                        }
-
-                        // If this item matches the ignore filter, don't sync it.
-                        if ignore_globs
-                            .iter()
-                            .filter(|pattern| pattern.matches(&item.path))
-                            .count()
-                            > 0
-                        {
-                            continue;
-                        }
-
-                        let remote_path_string = item.path.clone();
-                        let local_path_string = format!(
-                            "{}/{}",
-                            sync_dir.local_path,
-                            item.path.strip_prefix(&sync_dir.remote_path).unwrap()
-                        );
-                        update_ui_progress(&remote_path_string);
-
-                        // If we've already synced this directory from `fn sync_local_directory`
-                        // above, don't sync it again.
-                        if synced_items
-                            .borrow()
-                            .contains(&(local_path_string.clone(), remote_path_string.clone()))
-                        {
-                            continue;
-                        }
+                    check_open_requests();
+                    process_deletion_requests();
 
-                        let local_path = Path::new(&local_path_string);
-                        let remote_timestamp = item.mod_time.unix_timestamp();
-                        let get_local_file_timestamp = || {
-                            local_path.metadata().ok().map(|metadata| {
-                                metadata
-                                    .modified()
-                                    .unwrap()
-                                    .duration_since(SystemTime::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs()
-                            })
-                        };
-                        let local_timestamp = get_local_file_timestamp();
-                        let db_item = util::await_future(
-                            SyncItemsEntity::find()
-                                .filter(SyncItemsColumn::LocalPath.eq(local_path_string.clone()))
-                                .filter(SyncItemsColumn::RemotePath.eq(remote_path_string.clone()))
-                                .one(db),
-                        )
-                        .unwrap();
+                    // If this directory was just removed by a deletion request, there's no UI
+                    // left to update.
+                    let ptr = directory_map.get_ref();
+                    let dir_pair = (sync_dir.local_path.clone(), sync_dir.remote_path.clone());
+                    if let Some(item) = ptr.get(&remote.name).and_then(|dirs| dirs.get(&dir_pair)) {
+                        item.status_text.set_label(&status_string);
+                        // The label gets cut off when there's a lot of progress to show.
+                        item.status_text.set_tooltip_text(Some(&status_string));
+                    }
+                });
+                let engine = SyncEngine::new(
+                    db.clone(),
+                    remote.clone(),
+                    sync_dir.clone(),
+                    (*CLOSE_REQUEST).clone(),
+                    boxed::Box::new(on_event),
+                );
 
-                        // Push the item from the local machine to the remote machine. Returns the
-                        // timestamp of the new file on the remote. Returns the
-                        // [`crate::rclone::sync::RcloneRemoteItem`] of the item on the remote, or
-                        // an [`Err<()>`] if an issue occurred (all errors are automatically added
-                        // via `add_errors`).
-                        let push_local_to_remote = || {
-                            if local_path.is_dir() {
-                                if !item.is_dir {
-                                    if let Err(err) =
-                                        rclone::sync::delete(&remote.name, &remote_path_string)
-                                    {
-                                        add_error(SyncError::General(
-                                            remote_path_string.clone(),
-                                            err.error,
-                                        ));
-                                        return Err(());
-                                    }
+                // Get the local changes since the last sync. If the directory can't be
+                // watched (i.e. because it doesn't exist right now), we have to fall back to
+                // checking everything.
+                if watchers
+                    .get(&sync_dir.id)
+                    .is_some_and(|watcher| !watcher.is_watching())
+                {
+                    watchers.remove(&sync_dir.id);
+                }
+                if !watchers.contains_key(&sync_dir.id)
+                    && let Ok(watcher) = LocalWatcher::new(&sync_dir.local_path)
+                {
+                    watchers.insert(sync_dir.id, watcher);
+                }
 
-                                    if let Err(err) =
-                                        rclone::sync::mkdir(&remote.name, &remote_path_string)
-                                    {
-                                        add_error(SyncError::General(
-                                            remote_path_string.clone(),
-                                            err.error,
-                                        ));
-                                        return Err(());
-                                    }
-                                }
+                let local_changes = watchers
+                    .get_mut(&sync_dir.id)
+                    .map_or(LocalChanges::Full, |watcher| watcher.changes());
 
-                                sync_remote_directory(
-                                    &item.path,
-                                    remote,
-                                    sync_dir,
-                                    db,
-                                    directory_map,
-                                    synced_items,
-                                    add_error.clone(),
-                                    check_open_requests.clone(),
-                                    process_deletion_requests.clone(),
-                                );
-                                update_ui_progress(&remote_path_string);
-                            } else {
-                                if item.is_dir {
-                                    if let Err(err) =
-                                        rclone::sync::purge(&remote.name, &remote_path_string)
-                                    {
-                                        add_error(SyncError::General(
-                                            remote_path_string.clone(),
-                                            err.error,
-                                        ));
-                                        return Err(());
-                                    }
-                                }
+                // Paused directories don't get checked at all, though their watchers keep
+                // collecting changes for when they're resumed.
+                if settings.paused || sync_dir.paused {
+                    process_deletion_requests();
+                    missing_roots.remove(&sync_dir.id);
 
-                                if let Err(err) = rclone::sync::copy_to_remote(
-                                    &local_path_string,
-                                    &remote.name,
-                                    &remote_path_string,
-                                ) {
-                                    add_error(SyncError::General(
-                                        remote_path_string.clone(),
-                                        err.error,
-                                    ));
-                                    return Err(());
-                                }
-                            }
+                    let item_ptr = directory_map.get_ref();
+                    let dir_pair = (sync_dir.local_path.clone(), sync_dir.remote_path.clone());
+                    if let Some(item) = item_ptr
+                        .get(&remote.name)
+                        .and_then(|dirs| dirs.get(&dir_pair))
+                    {
+                        item.status_text.set_label(&tr::tr!("Syncing is paused."));
+                        item.status_icon
+                            .set_child(Some(&get_image("media-playback-pause-symbolic")));
+                    }
 
-                            Ok(rclone::sync::stat(&remote.name, &remote_path_string)
-                                .unwrap()
-                                .unwrap())
-                        };
+                    continue;
+                }
 
-                        // Pull the item from the remote to the local machine.
-                        let pull_remote_to_local = || {
-                            // Make sure file types match up.
-                            if local_path.exists() {
-                                if local_path.is_dir() && !item.is_dir {
-                                    if let Err(err) = fs::remove_dir_all(local_path) {
-                                        add_error(SyncError::General(
-                                            local_path_string.clone(),
-                                            err.to_string(),
-                                        ));
-                                        return Err(());
-                                    }
-                                } else if !local_path.is_dir() && item.is_dir {
-                                    if let Err(err) = fs::remove_file(local_path) {
-                                        add_error(SyncError::General(
-                                            local_path_string.clone(),
-                                            err.to_string(),
-                                        ));
-                                        return Err(());
-                                    }
+                // Everything below a missing root would look deleted, so leave the directory
+                // alone until the root is back.
+                missing_root.set(engine.missing_root());
 
-                                    if let Err(err) = fs::create_dir(local_path) {
-                                        add_error(SyncError::General(
-                                            local_path_string.clone(),
-                                            err.to_string(),
-                                        ));
-                                        return Err(());
-                                    }
-                                }
-                            }
+                // Check the local and remote files against the last sync state in the
+                // database, and only continue with syncing if they don't match.
+                let should_sync =
+                    missing_root.get().is_none() && engine.should_sync(&local_changes);
 
-                            if item.is_dir {
-                                if !local_path.exists()
-                                    && let Err(err) = fs::create_dir(local_path)
-                                {
-                                    add_error(SyncError::General(
-                                        local_path_string.clone(),
-                                        err.to_string(),
-                                    ));
-                                    return Err(());
-                                }
+                // There's no point in checking the remote's other directories if it can't be
+                // reached.
+                if disconnected.get_ref().is_some() {
+                    break;
+                }
 
-                                sync_remote_directory(
-                                    &item.path,
-                                    remote,
-                                    sync_dir,
-                                    db,
-                                    directory_map,
-                                    synced_items,
-                                    add_error.clone(),
-                                    check_open_requests.clone(),
-                                    process_deletion_requests.clone(),
-                                );
-                                update_ui_progress(&remote_path_string);
-                            } else if let Err(err) = rclone::sync::copy_to_local(
-                                &local_path_string,
-                                &remote.name,
-                                &remote_path_string,
-                            ) {
-                                add_error(SyncError::General(
-                                    remote_path_string.clone(),
-                                    err.error,
-                                ));
-                                return Err(());
-                            }
+                let item_ptr = directory_map.get_ref();
+                let item = item_ptr
+                    .get(&remote.name)
+                    .unwrap()
+                    .get(&(sync_dir.local_path.clone(), sync_dir.remote_path.clone()))
+                    .unwrap();
 
-                            Ok(())
-                        };
-                        // Delete this item from the database.
-                        let delete_db_entry = || {
-                            util::await_future(async {
-                                SyncItemsEntity::find()
-                                    .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
-                                    .filter(
-                                        SyncItemsColumn::LocalPath.eq(local_path_string.clone()),
-                                    )
-                                    .filter(
-                                        SyncItemsColumn::RemotePath.eq(remote_path_string.clone()),
-                                    )
-                                    .one(db)
-                                    .await
-                                    .unwrap()
-                                    .unwrap()
-                                    .delete(db)
-                                    .await
-                                    .unwrap()
-                            })
-                        };
+                if let Some(root) = missing_root.get() {
+                    show_root_missing(item, root, &sync_dir);
+                    missing_roots.insert(sync_dir.id);
+                    continue;
+                }
 
-                        // If we have a database record, use that in checks.
-                        if let Some(db_model) = db_item {
-                            let update_db_item = |local_timestamp, remote_timestamp| {
-                                let mut active_model: SyncItemsActiveModel =
-                                    db_model.clone().into();
-                                active_model.last_local_timestamp =
-                                    ActiveValue::Set(local_timestamp);
-                                active_model.last_remote_timestamp =
-                                    ActiveValue::Set(remote_timestamp);
-                                util::await_future(active_model.update(db)).unwrap();
-                            };
-
-                            // Both items are more recent.
-                            if let Some(l_timestamp) = local_timestamp
-                                && l_timestamp > db_model.last_local_timestamp as u64
-                                && remote_timestamp > db_model.last_remote_timestamp as i64
-                            {
-                                // Only add the error if one of the items is not a directory -
-                                // there's no point in saying both directories are more current, and
-                                // it's probably because one of the items in the directory got
-                                // updated anyway.
-                                if !local_path.is_dir() || !item.is_dir {
-                                    add_error(SyncError::BothMoreCurrent(
-                                        local_path_string.clone(),
-                                        remote_path_string.clone(),
-                                    ));
-                                }
-                                continue;
-                            // The local item is more recent.
-                            } else if let Some(l_timestamp) = local_timestamp
-                                && l_timestamp > db_model.last_local_timestamp as u64
-                            {
-                                if let Ok(rclone_item) = push_local_to_remote() {
-                                    update_db_item(
-                                        get_local_file_timestamp().unwrap().try_into().unwrap(),
-                                        rclone_item.mod_time.unix_timestamp().try_into().unwrap(),
-                                    );
-                                    continue;
-                                } else {
-                                    continue;
-                                }
+                missing_roots.remove(&sync_dir.id);
 
-                            // The remote item is more recent.
-                            } else if remote_timestamp > db_model.last_remote_timestamp as i64 {
-                                if pull_remote_to_local().is_err() {
-                                    continue;
-                                } else {
-                                    update_db_item(
-                                        get_local_file_timestamp().unwrap().try_into().unwrap(),
-                                        remote_timestamp.try_into().unwrap(),
-                                    );
-                                }
+                if !should_sync {
+                    if let Some(watcher) = watchers.get_mut(&sync_dir.id) {
+                        watcher.clear(&local_changes);
+                    }
 
-                            // The item is missing locally, but the last
-                            // recorded timestamp for the remote item is still
-                            // the same. This means the item got deleted
-                            // locally, and we need to reflect such on the
-                            // server.
-                            } else if !local_path.exists()
-                                && remote_timestamp == db_model.last_remote_timestamp as i64
-                            {
-                                if let Err(err) =
-                                    rclone::sync::purge(&remote.name, &remote_path_string)
-                                {
-                                    add_error(SyncError::General(
-                                        remote_path_string.clone(),
-                                        err.error,
-                                    ));
-                                    delete_db_entry();
-                                    continue;
-                                } else {
-                                    continue;
-                                }
+                    item.status_text.set_label(&tr::tr!("Files are synced."));
+                    item.status_icon
+                        .set_child(Some(&get_image("object-select-symbolic")));
+                    continue;
+                }
 
-                            // Both the local and remote item remain unchanged -
-                            // do nothing.
-                            } else if let Some(l_timestamp) = local_timestamp
-                                && l_timestamp == db_model.last_local_timestamp as u64
-                                && remote_timestamp == db_model.last_remote_timestamp as i64
-                            {
-                                continue;
+                // Notify the tray app that we're syncing this remote now. We
+                // do this here instead for each remote, because the above
+                // sync dir check might make us not change anything on this remote.
+                handle.update(|tray| {
+                    tray.set_msg(tr::tr!("Syncing '{}'...", remote.name));
+                    tray.set_syncing();
+                });
 
-                            // Every possible scenario should have been covered
-                            // above, so panic if not.
-                            } else {
-                                unreachable!();
-                            }
-                        // Otherwise just check the local timestamps against
-                        // those on th remote, and record our new transaction in
-                        // the database.
-                        } else {
-                            // If the local timestamp exists, then compare local and remote
-                            // timestamps.
-                            if let Some(l_timestamp) = local_timestamp {
-                                if l_timestamp > remote_timestamp as u64 {
-                                    if push_local_to_remote().is_err() {
-                                        continue;
-                                    }
-                                } else if pull_remote_to_local().is_err() {
-                                    continue;
-                                }
+                // If we have pending errors that need resolved, don't sync this directory.
+                if item.error_status_text.text().len() != 0 {
+                    continue;
+                }
 
-                            // Otherwise the local item didn't exist, so just
-                            // sync it from the remote.
-                            } else if pull_remote_to_local().is_err() {
-                                continue;
-                            }
-                        }
+                // Set up the UI for notifying the user that this directory is being synced.
+                // The width/height and margins for this are based on those from `get_image()`
+                // at the top of this file, as they're placed at the same place in the UI.
+                let spinner = Spinner::builder()
+                    .spinning(true)
+                    .width_request(4)
+                    .height_request(4)
+                    .margin_start(3)
+                    .margin_end(3)
+                    .build();
+                item.status_icon.set_child(Some(&spinner));
+                item.status_text
+                    .set_label(&tr::tr!("Checking for changes..."));
+                // Dropping this is important, otherwise the pointer borrow might last a lot
+                // longer and other parts of the code won't be able to get a pointer to the
+                // directory indexmap.
+                drop(item_ptr);
 
-                        // The local item is now guaranteed to exist. Also fetch the remote's
-                        // timestamp in case it got updated above.
-                        let l_timestamp = get_local_file_timestamp().unwrap();
-                        let r_timestamp =
-                            match rclone::sync::stat(&remote.name, &remote_path_string) {
-                                Ok(item) => item.unwrap().mod_time.unix_timestamp(),
-                                Err(err) => {
-                                    add_error(SyncError::General(
-                                        remote_path_string.clone(),
-                                        err.error,
-                                    ));
-                                    continue;
-                                }
-                            };
-
-                        // Record the current transaction's timestamps in the database.
-                        util::await_future(
-                            SyncItemsActiveModel {
-                                sync_dir_id: ActiveValue::Set(sync_dir.id),
-                                local_path: ActiveValue::Set(local_path_string.clone()),
-                                remote_path: ActiveValue::Set(remote_path_string.clone()),
-                                last_local_timestamp: ActiveValue::Set(
-                                    l_timestamp.try_into().unwrap(),
-                                ),
-                                last_remote_timestamp: ActiveValue::Set(
-                                    r_timestamp.try_into().unwrap(),
-                                ),
-                                ..Default::default()
-                            }
-                            .insert(db),
-                        )
-                        .unwrap();
-                    }
+                let allow_mass_deletion = confirmed_deletions.get_mut_ref().remove(&sync_dir.id);
+                engine.sync(allow_mass_deletion);
+                // Items that are only pending, i.e. because they're waiting to be retried,
+                // shouldn't make us poll more often.
+                if engine.changed() {
+                    changes_found = true;
                 }
-
-                sync_local_directory(
-                    Path::new(&sync_dir.local_path),
-                    &remote,
-                    &sync_dir,
-                    &db,
-                    &directory_map,
-                    &synced_items,
-                    &add_error,
-                    &check_open_requests,
-                    &process_deletion_requests,
-                );
-                sync_remote_directory(
-                    &sync_dir.remote_path,
-                    &remote,
-                    &sync_dir,
-                    &db,
-                    &directory_map,
-                    &synced_items,
-                    &add_error,
-                    &check_open_requests,
-                    &process_deletion_requests,
-                );
+                handle.update(|tray| tray.set_transfers(vec![]));
 
                 // If a close request was sent in, quit.
                 if *(*CLOSE_REQUEST).lock().unwrap() {
@@ -2574,12 +3214,18 @@ pub fn launch(app: &Application, background: bool) {
                 }
 
                 // If this sync directory doesn't exist anymore (from being deleted during
-                // `process_deletion_requests` calls in the about two functions), go to the next
-                // sync directory.
+                // `process_deletion_requests` calls while syncing above), go to the next sync
+                // directory.
                 if !sync_dir.exists(&db) {
                     continue 'main;
                 }
 
+                // The remote went offline while syncing. Any changes that didn't get synced
+                // are still around for when it's back.
+                if disconnected.get_ref().is_some() {
+                    break;
+                }
+
                 // Set up the UI for notifying the user that this directory has been synced.
                 let item_ptr = directory_map.get_ref();
                 let item = item_ptr
@@ -2587,6 +3233,14 @@ pub fn launch(app: &Application, background: bool) {
                     .unwrap()
                     .get(&(sync_dir.local_path.clone(), sync_dir.remote_path.clone()))
                     .unwrap();
+
+                // The root went missing while syncing.
+                if let Some(root) = missing_root.get() {
+                    show_root_missing(item, root, &sync_dir);
+                    missing_roots.insert(sync_dir.id);
+                    continue;
+                }
+
                 item.status_icon
                     .set_child(Some(&get_image("object-select-symbolic")));
                 let mut finished_text = tr::tr!("Files are synced.");
@@ -2597,22 +3251,124 @@ pub fn launch(app: &Application, background: bool) {
                 } else {
                     item.status_icon
                         .set_child(Some(&get_image("object-select-symbolic")));
+
+                    // Keep the changes around if anything failed, so they get checked again
+                    // once the errors are resolved. The files the sync wrote itself show up
+                    // as changes too, which would otherwise trigger another check right away.
+                    if let Some(watcher) = watchers.get_mut(&sync_dir.id) {
+                        watcher.clear(&local_changes);
+                        watcher.forget_unchanged(|path| engine.is_local_change(path));
+                    }
                 }
                 item.status_text.set_label(&finished_text);
                 drop(item_ptr);
             }
+
+            // Show whether the remote can be reached in the sidebar and its directories.
+            // An offline remote finds no changes, so it keeps getting retried with
+            // backoff.
+            let sidebar_page = stack
+                .child_by_name(&remote.name)
+                .map(|child| stack.page(&child));
+
+            if disconnected.get_ref().is_some() {
+                offline_remotes.insert(remote.id, remote.name.clone());
+
+                if let Some(page) = &sidebar_page {
+                    page.set_title(&tr::tr!("{} (Offline)", remote.name));
+                }
+
+                if let Some(dirs) = directory_map.get_ref().get(&remote.name) {
+                    for item in dirs.values() {
+                        item.status_text.set_label(&tr::tr!(
+                            "Unable to connect to the remote. Trying again automatically..."
+                        ));
+                        item.status_icon
+                            .set_child(Some(&get_image("network-offline-symbolic")));
+                    }
+                }
+            } else if offline_remotes.remove(&remote.id).is_some()
+                && let Some(page) = &sidebar_page
+            {
+                page.set_title(&remote.name);
+            }
+
+            if let Some(poller) = pollers.get_mut(&remote.id) {
+                poller.checked(changes_found);
+            }
         }
 
+        watchers.retain(|sync_dir_id, _| seen_sync_dirs.contains(sync_dir_id));
+        missing_roots.retain(|sync_dir_id| seen_sync_dirs.contains(sync_dir_id));
+        offline_remotes.retain(|remote_id, _| remote_ids.contains(remote_id));
+        quotas.retain(|remote_id, _| remote_ids.contains(remote_id));
+        quota_checks.retain(|remote_id, _| remote_ids.contains(remote_id));
+        quota_warnings.retain(|remote_id| remote_ids.contains(remote_id));
+
+        // Show the storage usage of each remote in the tray menu.
+        let mut tray_quotas: Vec<(String, String)> = quotas
+            .values()
+            .filter_map(|(name, about)| Some((name.clone(), about.summary()?)))
+            .collect();
+        tray_quotas.sort();
+        handle.update(|tray| tray.set_quotas(tray_quotas));
+
+        // Show the items synced most recently in the tray menu.
+        let recent: Vec<String> = util::await_future(
+            SyncEventsEntity::find()
+                .filter(SyncEventsColumn::Result.eq(SyncResult::Success))
+                .order_by_desc(SyncEventsColumn::Timestamp)
+                .limit(TRAY_RECENT_LIMIT)
+                .all(&db),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|event| {
+            let name = Path::new(&event.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or(event.path);
+            tr::tr!("{} '{}'", event.action.label(), name)
+        })
+        .collect();
+        handle.update(|tray| tray.set_recent(recent));
+
         // Notify that we've finished checking all remotes for changes.
         let error_count = sync_errors_count();
+        handle.update(|tray| tray.set_paused(settings.paused));
 
-        if error_count != 0 {
+        if settings.paused {
+            handle.update(|tray| tray.set_msg(tr::tr!("Syncing is paused.")));
+        } else if error_count != 0 {
             let error_msg = if error_count == 1 {
                 "Finished sync checks with 1 error.".to_string()
             } else {
                 tr::tr!("Finished sync checks with {} errors.", error_count)
             };
             handle.update(|tray| tray.set_msg(error_msg));
+        } else if !offline_remotes.is_empty() {
+            let offline_msg = if offline_remotes.len() == 1 {
+                tr::tr!(
+                    "Unable to connect to '{}'.",
+                    offline_remotes.values().next().unwrap()
+                )
+            } else {
+                tr::tr!("Unable to connect to {} remotes.", offline_remotes.len())
+            };
+            handle.update(|tray| {
+                tray.set_msg(offline_msg);
+                tray.set_disconnected();
+            });
+        } else if !missing_roots.is_empty() {
+            let missing_msg = if missing_roots.len() == 1 {
+                tr::tr!("Sync root missing for 1 directory.")
+            } else {
+                tr::tr!("Sync root missing for {} directories.", missing_roots.len())
+            };
+            handle.update(|tray| {
+                tray.set_msg(missing_msg);
+                tray.set_root_missing();
+            });
         } else {
             handle.update(|tray| {
                 tray.set_msg(tr::tr!("Finished sync checks."));
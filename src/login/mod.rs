@@ -3,7 +3,7 @@ use crate::{
     entities::{RemotesActiveModel, RemotesModel},
     gtk_util,
     mpsc::{self, Sender},
-    rclone,
+    rclone::{self, RcloneErrorKind, Remote, WebDavVendors},
     traits::prelude::*,
     util,
 };
@@ -23,13 +23,13 @@ use adw::{
     Application, ApplicationWindow, ComboRow, EntryRow, HeaderBar,
 };
 use dropbox::DropboxConfig;
-use gdrive::GDriveConfig;
+use gdrive::{AuthType, GDriveConfig};
 use nextcloud::NextcloudConfig;
 use owncloud::OwncloudConfig;
 use pcloud::PCloudConfig;
 use proton_drive::ProtonDriveConfig;
 use std::{cell::RefCell, rc::Rc};
-use webdav::WebDavConfig;
+use webdav::{WebDavConfig, WebDavType};
 
 use sea_orm::{entity::prelude::*, ActiveValue, DatabaseConnection};
 use serde_json::json;
@@ -69,19 +69,121 @@ impl ToString for ServerType {
     }
 }
 
+impl ServerType {
+    /// Get the name of the remote to create.
+    fn server_name(&self) -> String {
+        match self {
+            Self::Dropbox(config) => config.server_name.clone(),
+            Self::GDrive(config) => config.server_name.clone(),
+            Self::Nextcloud(config) => config.server_name.clone(),
+            Self::Owncloud(config) => config.server_name.clone(),
+            Self::PCloud(config) => config.server_name.clone(),
+            Self::ProtonDrive(config) => config.server_name.clone(),
+            Self::WebDav(config) => config.server_name.clone(),
+        }
+    }
+
+    /// Get the query for Rclone's `config/create` command.
+    fn config_query(&self) -> serde_json::Value {
+        match self {
+            Self::Dropbox(config) => json!({
+                "name": self.server_name(),
+                "parameters": {
+                    "client_id": config.client_id,
+                    "client_secret": config.client_secret,
+                    "token": config.auth_json,
+                    "config_refresh_token": false
+                },
+                "type": "dropbox"
+            }),
+            Self::GDrive(config) => json!({
+                "name": self.server_name(),
+                "parameters": {
+                    "client_id": config.client_id,
+                    "client_secret": config.client_secret,
+                    "token": config.auth_json,
+                    "config_refresh_token": false
+                },
+                "type": "drive"
+            }),
+            Self::Nextcloud(config) => json!({
+                "name": self.server_name(),
+                "parameters": {
+                    "url": config.server_url,
+                    "vendor": "nextcloud",
+                    "user": config.username,
+                    "pass": config.password
+                },
+                "type": "webdav",
+                "opt": {
+                    "obscure": true
+                }
+            }),
+            Self::Owncloud(config) => json!({
+                "name": self.server_name(),
+                "parameters": {
+                    "url": config.server_url,
+                    "vendor": "owncloud",
+                    "user": config.username,
+                    "pass": config.password
+                },
+                "type": "webdav",
+                "opt": {
+                    "obscure": true
+                }
+            }),
+            Self::PCloud(config) => json!({
+                "name": self.server_name(),
+                "parameters": {
+                    "client_id": config.client_id,
+                    "client_secret": config.client_secret,
+                    "token": config.auth_json,
+                    "config_refresh_token": false
+                },
+                "type": "pcloud",
+                "opt": {
+                    "obscure": true
+                }
+            }),
+            Self::ProtonDrive(config) => json!({
+                "name": self.server_name(),
+                "parameters": {
+                    "username": config.username,
+                    "password": config.password,
+                    "2fa": config.totp
+                },
+                "type": "protondrive",
+                "opt": {
+                    "obscure": true
+                }
+            }),
+            Self::WebDav(config) => json!({
+                "name": self.server_name(),
+                "parameters": {
+                    "url": config.server_url,
+                    "vendor": "webdav",
+                    "user": config.username,
+                    "pass": config.password
+                },
+                "type": "webdav",
+                "opt": {
+                    "obscure": true
+                }
+            }),
+        }
+    }
+}
+
 // Verify if a specific config can log in to a server.
 pub fn can_login(_app: &Application, config_name: &str) -> bool {
     if let Err(err) = rclone::sync::stat(config_name, "/") {
-        let err_msg = if err.error.contains("Temporary failure in name resolution") {
-            tr::tr!(
-                "Unable to connect to the server. Check your internet connection and try again."
-            )
-        } else if err.error.contains("this account requires a 2FA code") {
-            tr::tr!("A 2FA code is required to log in to this account. Provide one and try again.")
-        } else {
-            tr::tr!(
+        let err_msg = match err.kind() {
+            kind @ (RcloneErrorKind::Network | RcloneErrorKind::TwoFactorRequired) => {
+                format!("{} {}", kind.message(), kind.suggestion())
+            }
+            _ => tr::tr!(
                 "Unable to authenticate to the server. Check your login credentials and try again."
-            )
+            ),
         };
 
         gtk_util::show_error(&tr::tr!("Unable to log in"), Some(&err_msg));
@@ -218,102 +320,8 @@ pub fn login(app: &Application, db: &DatabaseConnection) -> Option<RemotesModel>
         window.set_sensitive(false);
 
         // Create a new config with the requested name.
-        let config_name = match &server {
-            ServerType::Dropbox(config) => config.server_name.clone(),
-            ServerType::GDrive(config) => config.server_name.clone(),
-            ServerType::Nextcloud(config) => config.server_name.clone(),
-            ServerType::Owncloud(config) => config.server_name.clone(),
-            ServerType::PCloud(config) => config.server_name.clone(),
-            ServerType::ProtonDrive(config) => config.server_name.clone(),
-            ServerType::WebDav(config) => config.server_name.clone(),
-        };
-
-        let config_query = match &server {
-            ServerType::Dropbox(config) => json!({
-                "name": config_name,
-                "parameters": {
-                    "client_id": config.client_id,
-                    "client_secret": config.client_secret,
-                    "token": config.auth_json,
-                    "config_refresh_token": false
-                },
-                "type": "dropbox"
-            }),
-            ServerType::GDrive(config) => json!({
-                "name": config_name,
-                "parameters": {
-                    "client_id": config.client_id,
-                    "client_secret": config.client_secret,
-                    "token": config.auth_json,
-                    "config_refresh_token": false
-                },
-                "type": "drive"
-            }),
-            ServerType::Nextcloud(config) => json!({
-                "name": config_name,
-                "parameters": {
-                    "url": config.server_url,
-                    "vendor": "nextcloud",
-                    "user": config.username,
-                    "pass": config.password
-                },
-                "type": "webdav",
-                "opt": {
-                    "obscure": true
-                }
-            }),
-            ServerType::Owncloud(config) => json!({
-                "name": config_name,
-                "parameters": {
-                    "url": config.server_url,
-                    "vendor": "owncloud",
-                    "user": config.username,
-                    "pass": config.password
-                },
-                "type": "webdav",
-                "opt": {
-                    "obscure": true
-                }
-            }),
-            ServerType::PCloud(config) => json!({
-                "name": config_name,
-                "parameters": {
-                    "client_id": config.client_id,
-                    "client_secret": config.client_secret,
-                    "token": config.auth_json,
-                    "config_refresh_token": false
-                },
-                "type": "pcloud",
-                "opt": {
-                    "obscure": true
-                }
-            }),
-            ServerType::ProtonDrive(config) => json!({
-                "name": config_name,
-                "parameters": {
-                    "username": config.username,
-                    "password": config.password,
-                    "2fa": config.totp
-                },
-                "type": "protondrive",
-                "opt": {
-                    "obscure": true
-                }
-            }),
-            ServerType::WebDav(config) => json!({
-                "name": config_name,
-                "parameters": {
-                    "url": config.server_url,
-                    "vendor": "webdav",
-                    "user": config.username,
-                    "pass": config.password
-                },
-                "type": "webdav",
-                "opt": {
-                    "obscure": true
-                }
-            }),
-        };
+        let config_name = server.server_name();
+        let config_query = server.config_query();
 
         util::run_in_background(move || {
             librclone::rpc("config/create", config_query.to_string()).unwrap()
@@ -343,3 +351,124 @@ pub fn login(app: &Application, db: &DatabaseConnection) -> Option<RemotesModel>
         }
     }
 }
+
+/// Log in to an existing remote again, i.e. after its login expired or its
+/// password got changed. The remote's Rclone config is updated in place, so its
+/// sync directories and their history are kept. Returns `true` if the client
+/// successfully logged in, and `false` on other events, such as closing the
+/// window before logging in.
+pub fn reconnect(app: &Application, remote_name: &str) -> bool {
+    let (sender, mut receiver) = mpsc::channel::<Option<ServerType>>();
+
+    // The window.
+    let window = ApplicationWindow::builder()
+        .application(app)
+        .title(&util::get_title!("Reconnect to {}", remote_name))
+        .width_request(400)
+        .build();
+    window.add_css_class("celeste-global-padding");
+    window.connect_close_request(glib::clone!(@strong sender => move |_| {
+        sender.send(None);
+        Inhibit(false)
+    }));
+
+    // Only the login form for the remote's type is needed.
+    let (rows, submit_button) = match rclone::get_remote(remote_name).unwrap() {
+        Remote::Dropbox(remote) => GDriveConfig::auth_sections(
+            &window,
+            sender,
+            AuthType::Dropbox,
+            remote.client_id,
+            remote.client_secret,
+        ),
+        Remote::GDrive(remote) => GDriveConfig::auth_sections(
+            &window,
+            sender,
+            AuthType::GDrive,
+            remote.client_id,
+            remote.client_secret,
+        ),
+        Remote::PCloud(remote) => GDriveConfig::auth_sections(
+            &window,
+            sender,
+            AuthType::PCloud,
+            remote.client_id,
+            remote.client_secret,
+        ),
+        Remote::ProtonDrive(_) => ProtonDriveConfig::get_sections(&window, sender),
+        Remote::WebDav(remote) => WebDavConfig::webdav_sections(
+            sender,
+            match remote.vendor {
+                WebDavVendors::Nextcloud => WebDavType::Nextcloud,
+                WebDavVendors::Owncloud => WebDavType::Owncloud,
+                _ => WebDavType::WebDav,
+            },
+        ),
+    };
+
+    // Every login form starts with the name of the remote, which has to stay the
+    // same. Rows that can't be edited aren't validated, so the name already being
+    // in use doesn't keep the form from being submitted.
+    let name_row = &rows[0];
+    name_row.set_editable(false);
+    name_row.set_text(remote_name);
+    name_row.remove_css_class("error");
+    name_row.set_tooltip_text(None);
+
+    let container = Box::builder().orientation(Orientation::Vertical).build();
+    let input_sections = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    container.append(&HeaderBar::new());
+    container.append(&input_sections);
+
+    for row in &rows {
+        input_sections.append(row);
+    }
+
+    container.append(&submit_button);
+    window.set_content(Some(&container));
+    window.show();
+
+    // Keep the current config around, so it can be put back if logging in fails.
+    let get_query = json!({ "name": remote_name });
+    let old_config = util::run_in_background(move || {
+        librclone::rpc("config/get", get_query.to_string()).unwrap()
+    });
+    let old_config: serde_json::Value = serde_json::from_str(&old_config).unwrap();
+
+    loop {
+        // If the user clicks the 'X' button on the window we get a [`None`] value.
+        let Some(server) = receiver.recv() else {
+            return false;
+        };
+        window.set_sensitive(false);
+
+        // Update the existing config instead of creating a new one.
+        let mut config_query = server.config_query();
+        config_query.as_object_mut().unwrap().remove("type");
+        util::run_in_background(move || {
+            librclone::rpc("config/update", config_query.to_string()).unwrap()
+        });
+
+        if can_login(app, remote_name) {
+            window.close();
+            return true;
+        }
+
+        // Put the old config back, which has its passwords obscured already.
+        let restore_query = json!({
+            "name": remote_name,
+            "parameters": old_config,
+            "opt": {
+                "nonInteractive": true,
+                "noObscure": true
+            }
+        });
+        util::run_in_background(move || {
+            librclone::rpc("config/update", restore_query.to_string()).unwrap()
+        });
+        window.set_sensitive(true);
+    }
+}
@@ -6,13 +6,16 @@
 #![feature(exit_status_error)]
 
 pub mod about;
+pub mod bwlimit;
 pub mod entities;
 pub mod gtk_util;
 pub mod launch;
 pub mod login;
 pub mod migrations;
 pub mod mpsc;
+pub mod preferences;
 pub mod rclone;
+pub mod sync;
 pub mod traits;
 pub mod tray;
 pub mod util;
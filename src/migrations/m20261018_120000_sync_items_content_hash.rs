@@ -0,0 +1,28 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE sync_items ADD COLUMN last_size INTEGER;
+            ALTER TABLE sync_items ADD COLUMN hash_type TEXT;
+            ALTER TABLE sync_items ADD COLUMN last_hash TEXT;
+        "#;
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = "
+            ALTER TABLE `sync_items` DROP COLUMN `last_hash`;
+            ALTER TABLE `sync_items` DROP COLUMN `hash_type`;
+            ALTER TABLE `sync_items` DROP COLUMN `last_size`;
+        ";
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+}
@@ -0,0 +1,26 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE sync_dirs ADD COLUMN mass_deletion_percent INTEGER NOT NULL DEFAULT 30;
+            ALTER TABLE sync_dirs ADD COLUMN mass_deletion_count INTEGER NOT NULL DEFAULT 500;
+        "#;
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = "
+            ALTER TABLE `sync_dirs` DROP COLUMN `mass_deletion_count`;
+            ALTER TABLE `sync_dirs` DROP COLUMN `mass_deletion_percent`;
+        ";
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+}
@@ -0,0 +1,31 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE settings (
+                id INTEGER PRIMARY KEY NOT NULL,
+                bandwidth_limit TEXT NOT NULL DEFAULT ''
+            );
+            INSERT INTO settings (id) VALUES (1);
+
+            ALTER TABLE remotes ADD COLUMN bandwidth_limit TEXT;
+        "#;
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = "
+            ALTER TABLE `remotes` DROP COLUMN `bandwidth_limit`;
+            DROP TABLE `settings`;
+        ";
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+}
@@ -0,0 +1,30 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE sync_retries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+                sync_dir_id INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                attempts INTEGER NOT NULL,
+                next_retry INTEGER NOT NULL,
+                last_error TEXT NOT NULL,
+                FOREIGN KEY(sync_dir_id) REFERENCES sync_dirs(id)
+            );
+        "#;
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = "DROP TABLE `sync_retries`;";
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+}
@@ -0,0 +1,28 @@
+use sea_orm::{ConnectionTrait, Statement};
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE sync_dirs ADD COLUMN version_storage TEXT NOT NULL DEFAULT 'off';
+            ALTER TABLE sync_dirs ADD COLUMN max_versions INTEGER NOT NULL DEFAULT 10;
+            ALTER TABLE sync_dirs ADD COLUMN max_version_days INTEGER NOT NULL DEFAULT 30;
+        "#;
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = "
+            ALTER TABLE `sync_dirs` DROP COLUMN `max_version_days`;
+            ALTER TABLE `sync_dirs` DROP COLUMN `max_versions`;
+            ALTER TABLE `sync_dirs` DROP COLUMN `version_storage`;
+        ";
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+        manager.get_connection().execute(stmt).await.map(|_| ())
+    }
+}
@@ -3,6 +3,22 @@ pub use sea_orm_migration::prelude::*;
 mod m20220101_000001_create_table;
 mod m20230207_204909_sync_dirs_remove_slash_suffix;
 mod m20230220_215840_remote_sync_items_fix;
+mod m20261018_120000_sync_items_content_hash;
+mod m20261018_130000_sync_dirs_conflict_policy;
+mod m20261018_140000_sync_dirs_sync_direction;
+mod m20261018_150000_sync_dirs_remote_trash_path;
+mod m20261018_160000_sync_dirs_mass_deletion_limits;
+mod m20261018_170000_remotes_max_transfers;
+mod m20261018_180000_bandwidth_limits;
+mod m20261018_190000_paused_flags;
+mod m20261018_200000_remotes_poll_interval;
+mod m20261018_210000_sync_retries;
+mod m20261018_220000_settings_quota_warning;
+mod m20261018_230000_sync_items_local_inode;
+mod m20261018_240000_sync_events;
+mod m20261018_250000_sync_dirs_versioning;
+mod m20261018_260000_sync_skipped_dirs;
+mod m20261018_270000_sync_retries_held_timestamp;
 
 pub struct Migrator;
 
@@ -13,6 +29,22 @@ impl MigratorTrait for Migrator {
             Box::new(m20220101_000001_create_table::Migration),
             Box::new(m20230207_204909_sync_dirs_remove_slash_suffix::Migration),
             Box::new(m20230220_215840_remote_sync_items_fix::Migration),
+            Box::new(m20261018_120000_sync_items_content_hash::Migration),
+            Box::new(m20261018_130000_sync_dirs_conflict_policy::Migration),
+            Box::new(m20261018_140000_sync_dirs_sync_direction::Migration),
+            Box::new(m20261018_150000_sync_dirs_remote_trash_path::Migration),
+            Box::new(m20261018_160000_sync_dirs_mass_deletion_limits::Migration),
+            Box::new(m20261018_170000_remotes_max_transfers::Migration),
+            Box::new(m20261018_180000_bandwidth_limits::Migration),
+            Box::new(m20261018_190000_paused_flags::Migration),
+            Box::new(m20261018_200000_remotes_poll_interval::Migration),
+            Box::new(m20261018_210000_sync_retries::Migration),
+            Box::new(m20261018_220000_settings_quota_warning::Migration),
+            Box::new(m20261018_230000_sync_items_local_inode::Migration),
+            Box::new(m20261018_240000_sync_events::Migration),
+            Box::new(m20261018_250000_sync_dirs_versioning::Migration),
+            Box::new(m20261018_260000_sync_skipped_dirs::Migration),
+            Box::new(m20261018_270000_sync_retries_held_timestamp::Migration),
         ]
     }
 }
@@ -0,0 +1,88 @@
+//! The window for the application-wide settings.
+use crate::{
+    bwlimit::BandwidthSchedule,
+    entities::{SettingsActiveModel, SettingsModel},
+    util,
+};
+use adw::{
+    glib,
+    gtk::{Align, SpinButton},
+    prelude::*,
+    ActionRow, Application, EntryRow, PreferencesGroup, PreferencesPage, PreferencesWindow,
+};
+use sea_orm::{ActiveModelTrait, ActiveValue, DatabaseConnection};
+
+/// Validate a bandwidth limit schedule in an [`EntryRow`], marking the row as
+/// invalid if it isn't. Returns the schedule if it's valid.
+pub fn validate_bandwidth_limit(row: &EntryRow) -> Option<String> {
+    let schedule = row.text().trim().to_owned();
+
+    match BandwidthSchedule::parse(&schedule) {
+        Ok(_) => {
+            row.remove_css_class("error");
+            row.set_tooltip_text(None);
+            Some(schedule)
+        }
+        Err(err) => {
+            row.add_css_class("error");
+            row.set_tooltip_text(Some(&err));
+            None
+        }
+    }
+}
+
+pub fn preferences_window(app: &Application, db: &DatabaseConnection) {
+    let settings = SettingsModel::get(db);
+
+    let bandwidth_group = PreferencesGroup::builder()
+        .title(&tr::tr!("Bandwidth"))
+        .description(&tr::tr!("A single rate such as '1M' limits transfers to 1 MB/s, and '1M:10M' sets separate upload and download limits. A timetable such as '09:00,1M 18:00,off' limits transfers during working hours only. Remotes can override this in their settings."))
+        .build();
+    let bandwidth_row = EntryRow::builder()
+        .title(&tr::tr!("Bandwidth limit (empty for unlimited)"))
+        .text(&settings.bandwidth_limit)
+        .show_apply_button(true)
+        .build();
+    bandwidth_row.connect_apply(glib::clone!(@strong db, @strong settings => move |row| {
+        let Some(schedule) = validate_bandwidth_limit(row) else {
+            return;
+        };
+        let mut active_model: SettingsActiveModel = settings.clone().into();
+        active_model.bandwidth_limit = ActiveValue::Set(schedule);
+        util::await_future(active_model.update(&db)).unwrap();
+    }));
+    bandwidth_group.add(&bandwidth_row);
+
+    let storage_group = PreferencesGroup::builder()
+        .title(&tr::tr!("Storage"))
+        .build();
+    let quota_warning_button = SpinButton::with_range(0.0, 100.0, 1.0);
+    quota_warning_button.set_valign(Align::Center);
+    quota_warning_button.set_value(settings.quota_warning as f64);
+    quota_warning_button.connect_value_changed(
+        glib::clone!(@strong db, @strong settings => move |button| {
+            let mut active_model: SettingsActiveModel = settings.clone().into();
+            active_model.quota_warning = ActiveValue::Set(button.value_as_int());
+            util::await_future(active_model.update(&db)).unwrap();
+        }),
+    );
+    let quota_warning_row = ActionRow::builder()
+        .title(&tr::tr!("Storage Warning"))
+        .subtitle(&tr::tr!(
+            "Notify when a remote's storage is fuller than this percentage, or 0 to never notify"
+        ))
+        .build();
+    quota_warning_row.add_suffix(&quota_warning_button);
+    storage_group.add(&quota_warning_row);
+
+    let page = PreferencesPage::new();
+    page.add(&bandwidth_group);
+    page.add(&storage_group);
+
+    let window = PreferencesWindow::builder()
+        .application(app)
+        .title(&util::get_title!("Preferences"))
+        .build();
+    window.add(&page);
+    window.show();
+}
@@ -1,9 +1,13 @@
 //! Structs and functions for use with Rclone RPC calls.
 use crate::util;
 use adw::glib;
+use nix::libc;
 use serde::Deserialize;
 use serde_json::json;
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
 use time::OffsetDateTime;
 
 /// Get a remote from the config file.
@@ -184,6 +188,299 @@ pub struct RcloneError {
     pub error: String,
 }
 
+impl RcloneError {
+    /// Get the kind of error this is, as parsed from its message.
+    pub fn kind(&self) -> RcloneErrorKind {
+        RcloneErrorKind::parse(&self.error)
+    }
+}
+
+/// The kinds of errors that can happen while talking to a remote. Rclone only
+/// returns a message for its errors, so these get parsed from that.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RcloneErrorKind {
+    /// The remote couldn't be reached at all, such as from DNS failures,
+    /// refused connections or timeouts.
+    Network,
+    /// The login for the remote expired or got revoked.
+    AuthExpired,
+    /// The account needs a 2FA code to log in.
+    TwoFactorRequired,
+    /// Access to the item was denied.
+    PermissionDenied,
+    /// There's no space left on the remote or the local disk.
+    QuotaExceeded,
+    /// The item's name isn't allowed by the remote.
+    InvalidName,
+    /// The item doesn't exist.
+    NotFound,
+    /// The remote is limiting how many requests can be made to it.
+    RateLimited,
+    /// Reading or writing something on the local machine failed.
+    LocalIo,
+    /// The transfer was cancelled by the user.
+    Cancelled,
+    /// Anything we don't know about.
+    Other,
+}
+
+impl RcloneErrorKind {
+    /// The patterns to find in a lowercased error message for each kind. The
+    /// first matching kind wins, so more specific kinds need to come first.
+    const PATTERNS: &'static [(Self, &'static [&'static str])] = &[
+        (Self::Cancelled, &["context canceled"]),
+        (
+            Self::Network,
+            &[
+                "temporary failure in name resolution",
+                "no such host",
+                "connection refused",
+                "connection reset by peer",
+                "network is unreachable",
+                "no route to host",
+                "i/o timeout",
+                "tls handshake timeout",
+                "context deadline exceeded",
+                "client.timeout exceeded",
+            ],
+        ),
+        (Self::TwoFactorRequired, &["2fa"]),
+        (
+            Self::AuthExpired,
+            &[
+                "invalid_grant",
+                "token expired",
+                "expired_access_token",
+                "couldn't fetch token",
+                "cannot fetch token",
+                "empty token found",
+                "401 unauthorized",
+                "status code 401",
+                "authentication failed",
+            ],
+        ),
+        (
+            Self::RateLimited,
+            &[
+                "too many requests",
+                "too_many_requests",
+                "rate limit",
+                "ratelimitexceeded",
+                "quota exceeded for quota metric",
+                "status code 429",
+            ],
+        ),
+        (
+            Self::QuotaExceeded,
+            &[
+                "storagequotaexceeded",
+                "storage quota has been exceeded",
+                "quota exceeded for this account",
+                "insufficient_space",
+                "insufficient storage",
+                "no space left on device",
+            ],
+        ),
+        (
+            Self::InvalidName,
+            &[
+                "invalid name",
+                "invalid character",
+                "malformed_path",
+                "disallowed_name",
+                "name too long",
+            ],
+        ),
+        (
+            Self::PermissionDenied,
+            &[
+                "permission denied",
+                "403 forbidden",
+                "status code 403",
+                "access denied",
+                "insufficient permissions",
+            ],
+        ),
+        (
+            Self::NotFound,
+            &[
+                "not found",
+                "no such file or directory",
+                "doesn't exist",
+                "status code 404",
+            ],
+        ),
+        (
+            Self::LocalIo,
+            &[
+                "input/output error",
+                "read-only file system",
+                "too many open files",
+            ],
+        ),
+    ];
+
+    /// Parse the kind of error from an error message from Rclone.
+    pub fn parse(error: &str) -> Self {
+        let error = error.to_lowercase();
+
+        Self::PATTERNS
+            .iter()
+            .find(|(_, patterns)| patterns.iter().any(|pattern| error.contains(pattern)))
+            .map(|(kind, _)| *kind)
+            .unwrap_or(Self::Other)
+    }
+
+    /// Get the kind of error for an error from the local filesystem.
+    pub fn from_io(error: &io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => Self::NotFound,
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            _ => match error.raw_os_error() {
+                Some(libc::ENOSPC | libc::EDQUOT) => Self::QuotaExceeded,
+                Some(libc::ENAMETOOLONG | libc::EILSEQ) => Self::InvalidName,
+                _ => Self::LocalIo,
+            },
+        }
+    }
+
+    /// Whether trying again later could work without the user having to do
+    /// anything. Errors we don't know about aren't, so the user gets to see
+    /// them right away.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Network | Self::RateLimited | Self::NotFound | Self::LocalIo
+        )
+    }
+
+    /// Whether the error affects the whole remote instead of a single item, in
+    /// which case syncing anything else would just fail the same way.
+    pub fn affects_remote(&self) -> bool {
+        matches!(
+            self,
+            Self::Network | Self::AuthExpired | Self::TwoFactorRequired | Self::RateLimited
+        )
+    }
+
+    /// Get a message describing the kind of error.
+    pub fn message(&self) -> String {
+        match self {
+            Self::Network => tr::tr!("Unable to connect to the server."),
+            Self::AuthExpired => tr::tr!("The login for this remote has expired."),
+            Self::TwoFactorRequired => tr::tr!("A 2FA code is required to log in."),
+            Self::PermissionDenied => tr::tr!("Permission to access this item was denied."),
+            Self::QuotaExceeded => tr::tr!("There's no space left to store this item."),
+            Self::InvalidName => tr::tr!("The remote doesn't allow this item's name."),
+            Self::NotFound => tr::tr!("The item couldn't be found."),
+            Self::RateLimited => tr::tr!("The server is limiting how often it can be synced with."),
+            Self::LocalIo => tr::tr!("Unable to read or write the item on this computer."),
+            Self::Cancelled => tr::tr!("The transfer was cancelled."),
+            Self::Other => tr::tr!("An unknown error occurred."),
+        }
+    }
+
+    /// Get a suggestion for how the user can fix the error.
+    pub fn suggestion(&self) -> String {
+        match self {
+            Self::Network => tr::tr!("Check your internet connection and try again."),
+            Self::AuthExpired => tr::tr!("Log in to the remote again."),
+            Self::TwoFactorRequired => tr::tr!("Provide a 2FA code and try again."),
+            Self::PermissionDenied => tr::tr!("Check that you have access to the item."),
+            Self::QuotaExceeded => tr::tr!("Free up some space and try again."),
+            Self::InvalidName => tr::tr!("Rename the item so the remote accepts it."),
+            Self::NotFound => tr::tr!("The item may have been moved. Try syncing again."),
+            Self::RateLimited => tr::tr!("Wait a while and try again."),
+            Self::LocalIo => tr::tr!("Check that the disk is working and try again."),
+            Self::Cancelled => tr::tr!("It will be tried again on the next sync."),
+            Self::Other => tr::tr!("Try syncing again, or dismiss the error."),
+        }
+    }
+}
+
+/// The output of an `operations/about` command. Remotes only report the
+/// values they know about, and some don't report any at all.
+#[derive(Clone, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct RcloneAbout {
+    /// The total amount of space in bytes.
+    pub total: Option<u64>,
+    /// The amount of space in use in bytes.
+    pub used: Option<u64>,
+    /// The amount of space left in bytes.
+    pub free: Option<u64>,
+}
+
+impl RcloneAbout {
+    /// Get the total amount of space, working it out from the used and free
+    /// space if the remote doesn't report it.
+    fn total_space(&self) -> Option<u64> {
+        self.total
+            .or_else(|| self.used.zip(self.free).map(|(used, free)| used + free))
+    }
+
+    /// Get the fraction of the total space that's in use, if the remote
+    /// reports enough to tell.
+    pub fn usage(&self) -> Option<f64> {
+        let total = self.total_space()?;
+        let used = self
+            .used
+            .or_else(|| self.free.map(|free| total.saturating_sub(free)))?;
+
+        (total != 0).then(|| used as f64 / total as f64)
+    }
+
+    /// Get a short summary of the space in use, if the remote reports any.
+    pub fn summary(&self) -> Option<String> {
+        let size = |bytes: u64| glib::format_size(bytes).to_string();
+
+        match (self.used, self.total_space(), self.free) {
+            (Some(used), Some(total), Some(free)) => Some(tr::tr!(
+                "{} of {} used ({} free)",
+                size(used),
+                size(total),
+                size(free)
+            )),
+            (Some(used), Some(total), None) => {
+                Some(tr::tr!("{} of {} used", size(used), size(total)))
+            }
+            (Some(used), None, _) => Some(tr::tr!("{} used", size(used))),
+            (None, _, Some(free)) => Some(tr::tr!("{} free", size(free))),
+            (None, _, None) => None,
+        }
+    }
+}
+
+/// The output of a command started with `_async` set, containing the ID of
+/// the job running it.
+#[derive(Clone, Deserialize, Debug)]
+pub struct RcloneJob {
+    jobid: u64,
+}
+
+/// The output of a `job/status` command.
+#[derive(Clone, Deserialize, Debug)]
+pub struct RcloneJobStatus {
+    /// Whether the job has finished, whether it succeeded or not.
+    pub finished: bool,
+    pub success: bool,
+    /// The error the job failed with, which is empty if it didn't.
+    pub error: String,
+}
+
+/// The output of a `core/stats` command.
+#[derive(Clone, Deserialize, Debug, Default)]
+pub struct RcloneStats {
+    /// The number of bytes transferred so far.
+    pub bytes: u64,
+    /// The number of bytes to transfer in total.
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+    /// The current transfer speed in bytes per second.
+    pub speed: f64,
+    /// The estimated number of seconds left, if known.
+    pub eta: Option<u64>,
+}
+
 /// The output of an `operations/stat` command.
 #[derive(Clone, Deserialize, Debug)]
 pub struct RcloneStat {
@@ -209,6 +506,27 @@ pub struct RcloneRemoteItem {
     pub name: String,
     #[serde(rename = "ModTime", with = "time::serde::rfc3339")]
     pub mod_time: OffsetDateTime,
+    /// The size of the item in bytes. This is `-1` for directories on some
+    /// remotes.
+    #[serde(rename = "Size", default)]
+    pub size: i64,
+    /// The hashes of the item's content, keyed by the hash type (i.e. `md5` or
+    /// `sha1`). This is empty for directories, for remotes that don't support
+    /// any hashes, and unless they were asked for (see
+    /// [`sync::stat_with_hashes`]).
+    #[serde(rename = "Hashes", default)]
+    pub hashes: BTreeMap<String, String>,
+}
+
+impl RcloneRemoteItem {
+    /// Get the hash type and hash to compare this item's content with. Any
+    /// hash type works for that, we just need to pick the same one each time.
+    pub fn hash(&self) -> Option<(&str, &str)> {
+        self.hashes
+            .iter()
+            .next()
+            .map(|(hash_type, hash)| (hash_type.as_str(), hash.as_str()))
+    }
 }
 
 /// The types of items to show in an `operations/list` command.
@@ -226,10 +544,15 @@ pub enum RcloneListFilter {
 /// Functions for syncing to a remote.
 /// All functions in this module automatically run under
 /// [`util::run_in_background`], so they don't need to be wrapped around
-/// such to be ran during UI execution.
+/// such to be ran during UI execution. They can also be called from other
+/// threads, in which case they simply block.
 pub mod sync {
-    use super::{RcloneError, RcloneList, RcloneListFilter, RcloneRemoteItem, RcloneStat};
+    use super::{
+        RcloneAbout, RcloneError, RcloneJob, RcloneJobStatus, RcloneList, RcloneListFilter,
+        RcloneRemoteItem, RcloneStat, RcloneStats,
+    };
     use crate::util;
+    use adw::glib;
     use serde_json::json;
 
     /// Get a remote name.
@@ -244,6 +567,13 @@ pub mod sync {
     fn run<T: ToString>(method: T, input: T) -> Result<String, String> {
         let method = method.to_string();
         let input = input.to_string();
+
+        // Outside of the GUI's thread there's nothing to keep running, and the main
+        // context can't be acquired anyway.
+        if !glib::MainContext::default().is_owner() {
+            return librclone::rpc(method, input);
+        }
+
         util::run_in_background(|| librclone::rpc(method, input))
     }
 
@@ -264,6 +594,17 @@ pub mod sync {
         }
     }
 
+    /// Limit the bandwidth used by all transfers, in the form of a single rate
+    /// from Rclone's `--bwlimit` option (i.e. `1M`, `1M:10M` or `off`).
+    pub fn set_bandwidth_limit(rate: &str) -> Result<(), RcloneError> {
+        let resp = run("core/bwlimit", &json!({ "rate": rate }).to_string());
+
+        match resp {
+            Ok(_) => Ok(()),
+            Err(json_str) => Err(serde_json::from_str(&json_str).unwrap()),
+        }
+    }
+
     /// Delete a config.
     pub fn delete_config(remote_name: &str) -> Result<(), RcloneError> {
         let resp = run("config/delete", &json!({ "name": remote_name }).to_string());
@@ -276,11 +617,30 @@ pub mod sync {
 
     /// Get statistics about a file or folder.
     pub fn stat(remote_name: &str, path: &str) -> Result<Option<RcloneRemoteItem>, RcloneError> {
+        stat_item(remote_name, path, false)
+    }
+
+    /// Get statistics about a file or folder, including the hashes of its
+    /// content. Some remotes have to do extra work for those, so they should
+    /// only be asked for when they're needed.
+    pub fn stat_with_hashes(
+        remote_name: &str,
+        path: &str,
+    ) -> Result<Option<RcloneRemoteItem>, RcloneError> {
+        stat_item(remote_name, path, true)
+    }
+
+    fn stat_item(
+        remote_name: &str,
+        path: &str,
+        show_hash: bool,
+    ) -> Result<Option<RcloneRemoteItem>, RcloneError> {
         let resp = run(
             "operations/stat",
             &json!({
                 "fs": get_remote_name(remote_name),
-                "remote": util::strip_slashes(path)
+                "remote": util::strip_slashes(path),
+                "opt": { "showHash": show_hash }
             })
             .to_string(),
         );
@@ -291,6 +651,41 @@ pub mod sync {
         }
     }
 
+    /// Get the storage usage of a remote.
+    pub fn about(remote_name: &str) -> Result<RcloneAbout, RcloneError> {
+        let resp = run(
+            "operations/about",
+            &json!({ "fs": get_remote_name(remote_name) }).to_string(),
+        );
+
+        match resp {
+            Ok(json_str) => Ok(serde_json::from_str(&json_str).unwrap()),
+            Err(json_str) => Err(serde_json::from_str(&json_str).unwrap()),
+        }
+    }
+
+    /// Get the hash of a local file, using the given hash type (i.e. one from
+    /// [`RcloneRemoteItem::hash`]).
+    pub fn local_hash(local_file: &str, hash_type: &str) -> Result<Option<String>, RcloneError> {
+        let resp = run(
+            "operations/stat",
+            &json!({
+                "fs": "/",
+                "remote": util::strip_slashes(local_file),
+                "opt": { "showHash": true, "hashTypes": [hash_type] }
+            })
+            .to_string(),
+        );
+
+        match resp {
+            Ok(json_str) => Ok(serde_json::from_str::<RcloneStat>(&json_str)
+                .unwrap()
+                .item
+                .and_then(|item| item.hashes.get(hash_type).cloned())),
+            Err(json_str) => Err(serde_json::from_str(&json_str).unwrap()),
+        }
+    }
+
     /// List the files/folders in a path.
     pub fn list(
         remote_name: &str,
@@ -334,12 +729,87 @@ pub mod sync {
         common("operations/purge", remote_name, path)
     }
 
-    /// Utility for copy functions.
+    /// Move a file on the remote to another path on the same remote. This is
+    /// done server-side if the remote supports it.
+    pub fn move_file(remote_name: &str, src_path: &str, dst_path: &str) -> Result<(), RcloneError> {
+        let remote_name = get_remote_name(remote_name);
+        let resp = run(
+            "operations/movefile",
+            &json!({
+                "srcFs": remote_name,
+                "srcRemote": util::strip_slashes(src_path),
+                "dstFs": remote_name,
+                "dstRemote": util::strip_slashes(dst_path)
+            })
+            .to_string(),
+        );
+
+        match resp {
+            Ok(_) => Ok(()),
+            Err(json_str) => Err(serde_json::from_str(&json_str).unwrap()),
+        }
+    }
+
+    /// Move a directory on the remote to another path on the same remote. This
+    /// is done server-side if the remote supports it.
+    pub fn move_dir(remote_name: &str, src_path: &str, dst_path: &str) -> Result<(), RcloneError> {
+        let fs = get_remote_name(remote_name);
+        let resp = run(
+            "sync/move",
+            &json!({
+                "srcFs": format!("{fs}{}", util::strip_slashes(src_path)),
+                "dstFs": format!("{fs}{}", util::strip_slashes(dst_path)),
+                "deleteEmptySrcDirs": true
+            })
+            .to_string(),
+        );
+
+        if let Err(json_str) = resp {
+            return Err(serde_json::from_str(&json_str).unwrap());
+        }
+
+        // Remove the source directory itself if the move left it behind. This only
+        // removes empty directories, so nothing gets lost if the above didn't move
+        // everything.
+        if stat(remote_name, src_path)?.is_some() {
+            common("operations/rmdirs", remote_name, src_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Utility for copy functions. The copy runs as a job in the background,
+    /// whose ID gets returned.
     fn copy(
         src_fs: &str,
         src_remote: &str,
         dst_fs: &str,
         dst_remote: &str,
+    ) -> Result<u64, RcloneError> {
+        let resp = run(
+            "operations/copyfile",
+            &json!({
+                "srcFs": src_fs,
+                "srcRemote": util::strip_slashes(src_remote),
+                "dstFs": dst_fs,
+                "dstRemote": util::strip_slashes(dst_remote),
+                "_async": true
+            })
+            .to_string(),
+        );
+
+        match resp {
+            Ok(json_str) => Ok(serde_json::from_str::<RcloneJob>(&json_str).unwrap().jobid),
+            Err(json_str) => Err(serde_json::from_str(&json_str).unwrap()),
+        }
+    }
+
+    /// Utility for copy functions that wait for the copy to finish.
+    fn copy_and_wait(
+        src_fs: &str,
+        src_remote: &str,
+        dst_fs: &str,
+        dst_remote: &str,
     ) -> Result<(), RcloneError> {
         let resp = run(
             "operations/copyfile",
@@ -358,12 +828,85 @@ pub mod sync {
         }
     }
 
-    /// Copy a file from the local machine to the remote.
-    pub fn copy_to_remote(
+    /// Copy a file on the remote to another path on the same remote. This is
+    /// done server-side if the remote supports it.
+    pub fn copy_file(remote_name: &str, src_path: &str, dst_path: &str) -> Result<(), RcloneError> {
+        let remote_name = get_remote_name(remote_name);
+        copy_and_wait(&remote_name, src_path, &remote_name, dst_path)
+    }
+
+    /// Copy a file from the local machine to the remote, waiting for the copy
+    /// to finish.
+    pub fn upload_file(
         local_file: &str,
         remote_name: &str,
         remote_destination: &str,
     ) -> Result<(), RcloneError> {
+        copy_and_wait(
+            "/",
+            local_file,
+            &get_remote_name(remote_name),
+            remote_destination,
+        )
+    }
+
+    /// Copy a file from the remote to the local machine, waiting for the copy
+    /// to finish.
+    pub fn download_file(
+        local_destination: &str,
+        remote_name: &str,
+        remote_file: &str,
+    ) -> Result<(), RcloneError> {
+        copy_and_wait(
+            &get_remote_name(remote_name),
+            remote_file,
+            "/",
+            local_destination,
+        )
+    }
+
+    /// Get the status of a job started in the background.
+    pub fn job_status(job_id: u64) -> Result<RcloneJobStatus, RcloneError> {
+        let resp = run("job/status", &json!({ "jobid": job_id }).to_string());
+
+        match resp {
+            Ok(json_str) => Ok(serde_json::from_str(&json_str).unwrap()),
+            Err(json_str) => Err(serde_json::from_str(&json_str).unwrap()),
+        }
+    }
+
+    /// Stop a job started in the background. The job then finishes with an
+    /// error of the [`RcloneErrorKind::Cancelled`] kind.
+    pub fn stop_job(job_id: u64) -> Result<(), RcloneError> {
+        let resp = run("job/stop", &json!({ "jobid": job_id }).to_string());
+
+        match resp {
+            Ok(_) => Ok(()),
+            Err(json_str) => Err(serde_json::from_str(&json_str).unwrap()),
+        }
+    }
+
+    /// Get the transfer statistics of a job started in the background.
+    pub fn job_stats(job_id: u64) -> Result<RcloneStats, RcloneError> {
+        // Each job gets its own stats group.
+        let resp = run(
+            "core/stats",
+            &json!({ "group": format!("job/{job_id}") }).to_string(),
+        );
+
+        match resp {
+            Ok(json_str) => Ok(serde_json::from_str(&json_str).unwrap()),
+            Err(json_str) => Err(serde_json::from_str(&json_str).unwrap()),
+        }
+    }
+
+    /// Start copying a file from the local machine to the remote, returning
+    /// the ID of the job doing so.
+    pub fn copy_to_remote(
+        local_file: &str,
+        remote_name: &str,
+        remote_destination: &str,
+    ) -> Result<u64, RcloneError> {
         copy(
             "/",
             local_file,
@@ -372,12 +915,13 @@ pub mod sync {
         )
     }
 
-    /// Copy a file from the remote to the local machine.
+    /// Start copying a file from the remote to the local machine, returning
+    /// the ID of the job doing so.
     pub fn copy_to_local(
         local_destination: &str,
         remote_name: &str,
         remote_file: &str,
-    ) -> Result<(), RcloneError> {
+    ) -> Result<u64, RcloneError> {
         copy(
             &get_remote_name(remote_name),
             remote_file,
@@ -386,3 +930,82 @@ pub mod sync {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_kinds() {
+        let cases = [
+            ("context canceled", RcloneErrorKind::Cancelled),
+            (
+                "dial tcp: lookup www.googleapis.com: no such host",
+                RcloneErrorKind::Network,
+            ),
+            (
+                "couldn't fetch token: invalid_grant",
+                RcloneErrorKind::AuthExpired,
+            ),
+            (
+                "googleapi: Error 429: Too Many Requests",
+                RcloneErrorKind::RateLimited,
+            ),
+            (
+                "googleapi: Error 403: User rate limit exceeded., userRateLimitExceeded",
+                RcloneErrorKind::RateLimited,
+            ),
+            (
+                "googleapi: Error 403: Quota exceeded for quota metric 'Queries', rateLimitExceeded",
+                RcloneErrorKind::RateLimited,
+            ),
+            (
+                "googleapi: Error 403: The user's Drive storage quota has been exceeded., \
+                 storageQuotaExceeded",
+                RcloneErrorKind::QuotaExceeded,
+            ),
+            ("object not found", RcloneErrorKind::NotFound),
+            (
+                "open /tmp/file: permission denied",
+                RcloneErrorKind::PermissionDenied,
+            ),
+            (
+                "read /tmp/file: input/output error",
+                RcloneErrorKind::LocalIo,
+            ),
+            ("something unexpected happened", RcloneErrorKind::Other),
+            // Only running out of storage counts, not any mention of a quota.
+            ("quota project not set", RcloneErrorKind::Other),
+        ];
+
+        for (error, kind) in cases {
+            assert_eq!(RcloneErrorKind::parse(error), kind, "{error}");
+        }
+    }
+
+    #[test]
+    fn unknown_errors_are_not_retried() {
+        assert!(RcloneErrorKind::Network.is_retryable());
+        assert!(RcloneErrorKind::RateLimited.is_retryable());
+        assert!(!RcloneErrorKind::QuotaExceeded.is_retryable());
+        assert!(!RcloneErrorKind::Other.is_retryable());
+    }
+
+    #[test]
+    fn parse_error_kind_is_case_insensitive() {
+        assert_eq!(
+            RcloneErrorKind::parse("Connection Refused"),
+            RcloneErrorKind::Network
+        );
+    }
+
+    #[test]
+    fn parse_prefers_earlier_kinds() {
+        // A cancelled request that also mentions a network error is still a
+        // cancellation.
+        assert_eq!(
+            RcloneErrorKind::parse("context canceled: connection reset by peer"),
+            RcloneErrorKind::Cancelled
+        );
+    }
+}
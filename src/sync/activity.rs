@@ -0,0 +1,74 @@
+//! The log of everything done while syncing.
+//!
+//! Each upload, download, deletion and move gets recorded along with how it
+//! turned out, so that the user can find out what happened to an item after
+//! the fact.
+use super::SyncError;
+use crate::{
+    entities::{SyncAction, SyncEventsActiveModel, SyncEventsColumn, SyncEventsEntity, SyncResult},
+    rclone::RcloneErrorKind,
+    util,
+};
+use sea_orm::{entity::prelude::*, ActiveValue, DatabaseConnection};
+use time::OffsetDateTime;
+
+/// The number of seconds to keep entries in the log for.
+const MAX_AGE: i64 = 30 * 24 * 60 * 60;
+
+/// The activity log of a sync directory.
+pub struct ActivityLog {
+    db: DatabaseConnection,
+    sync_dir_id: i32,
+}
+
+impl ActivityLog {
+    pub fn new(db: DatabaseConnection, sync_dir_id: i32) -> Self {
+        Self { db, sync_dir_id }
+    }
+
+    /// Record an operation on the item at `local_path`, along with the number
+    /// of bytes it transferred, if any, and its result.
+    pub fn record(
+        &self,
+        action: SyncAction,
+        local_path: &str,
+        bytes: Option<u64>,
+        result: Result<(), &SyncError>,
+    ) {
+        let (result, error) = match result {
+            Ok(()) => (SyncResult::Success, None),
+            Err(SyncError::General(_, RcloneErrorKind::Cancelled, _)) => {
+                (SyncResult::Cancelled, None)
+            }
+            Err(err) => (SyncResult::Failed, Some(err.message())),
+        };
+
+        util::await_future(
+            SyncEventsActiveModel {
+                sync_dir_id: ActiveValue::Set(self.sync_dir_id),
+                timestamp: ActiveValue::Set(OffsetDateTime::now_utc().unix_timestamp()),
+                path: ActiveValue::Set(local_path.to_owned()),
+                action: ActiveValue::Set(action),
+                bytes: ActiveValue::Set(bytes.map(|bytes| bytes as i64)),
+                result: ActiveValue::Set(result),
+                error: ActiveValue::Set(error),
+                ..Default::default()
+            }
+            .insert(&self.db),
+        )
+        .unwrap();
+    }
+
+    /// Remove the entries that are too old to be worth keeping.
+    pub fn prune(&self) {
+        let oldest = OffsetDateTime::now_utc().unix_timestamp() - MAX_AGE;
+
+        util::await_future(
+            SyncEventsEntity::delete_many()
+                .filter(SyncEventsColumn::SyncDirId.eq(self.sync_dir_id))
+                .filter(SyncEventsColumn::Timestamp.lt(oldest))
+                .exec(&self.db),
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,2268 @@
+//! The two-way sync engine.
+//!
+//! Nothing in this module touches the UI. A [`SyncEngine`] reports its
+//! progress, errors and conflicts through the callback it was created with, so
+//! that the GUI, a CLI or tests can all drive the same code.
+mod activity;
+mod plan;
+mod poll;
+mod retry;
+mod transfers;
+mod trash;
+mod versions;
+mod watcher;
+
+use crate::{
+    entities::{
+        ConflictPolicy, RemotesColumn, RemotesEntity, RemotesModel, SyncAction, SyncDirection,
+        SyncDirsColumn, SyncDirsEntity, SyncDirsModel, SyncEventsColumn, SyncEventsEntity,
+        SyncItemsActiveModel, SyncItemsColumn, SyncItemsEntity, SyncItemsModel, SyncRetriesColumn,
+        SyncRetriesEntity, SyncSkippedDirsActiveModel, SyncSkippedDirsColumn,
+        SyncSkippedDirsEntity,
+    },
+    rclone::{self, RcloneError, RcloneErrorKind, RcloneListFilter, RcloneRemoteItem},
+    util,
+};
+use activity::ActivityLog;
+use file_lock::{FileLock, FileOptions};
+use nix::unistd;
+use retry::Retries;
+use sea_orm::{entity::prelude::*, ActiveValue, DatabaseConnection};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{self, Metadata},
+    io,
+    os::unix::fs::MetadataExt,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+use transfers::{Transfer, TransferQueue};
+
+pub use plan::{PlanAction, PlannedItem, SyncPlan};
+pub use poll::PollScheduler;
+pub use transfers::{FileProgress, TransferKind, TransferProgress};
+pub use versions::{FileVersion, Versions, VERSIONS_DIR_NAME};
+pub use watcher::{LocalChanges, LocalWatcher};
+
+/// The name of the file containing ignore rules, relative to the local path of
+/// a sync directory.
+pub static FILE_IGNORE_NAME: &str = ".sync-exclude.lst";
+
+/// The minimum number of deletions before a sync directory's mass deletion
+/// percentage applies, so that deleting a couple of items from a small
+/// directory doesn't need confirmation.
+const MASS_DELETION_MIN_ITEMS: usize = 10;
+
+/// How often to report the progress of running transfers while waiting on
+/// them.
+const TRANSFER_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The errors that can be found while syncing.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SyncError {
+    /// A general catch-all error. A tuple of the path the error happened at,
+    /// the kind of error, and the error message itself.
+    General(String, RcloneErrorKind, String),
+    /// An error when both the local and remote file are more current than at
+    /// the last sync. A tuple of the local and remote file.
+    BothMoreCurrent(String, String),
+    /// An error when syncing would delete more items than the sync directory's
+    /// mass deletion limits allow. A tuple of the number of items that would
+    /// be deleted, and the number of items being synced.
+    MassDeletion(usize, usize),
+}
+
+impl SyncError {
+    /// Create an error from a failed Rclone call at `path`.
+    fn rclone(path: &str, err: RcloneError) -> Self {
+        Self::General(path.to_owned(), err.kind(), err.error)
+    }
+
+    /// Create an error from a failed call to the local filesystem at `path`.
+    fn local(path: &str, err: &io::Error) -> Self {
+        Self::General(
+            path.to_owned(),
+            RcloneErrorKind::from_io(err),
+            err.to_string(),
+        )
+    }
+
+    /// Get a message describing the error.
+    pub fn message(&self) -> String {
+        match self {
+            Self::General(_, _, err) => err.clone(),
+            Self::BothMoreCurrent(local_path, remote_path) => tr::tr!(
+                "Both '{}' and '{}' are more recent than at last sync.",
+                local_path,
+                remote_path
+            ),
+            Self::MassDeletion(deletions, total) => tr::tr!(
+                "Syncing would delete {} of the {} synced items.",
+                deletions,
+                total
+            ),
+        }
+    }
+}
+
+/// The events reported by a [`SyncEngine`] while it runs.
+#[derive(Clone, Debug)]
+pub enum SyncEvent {
+    /// A local path is being checked for changes.
+    CheckingLocal(String),
+    /// A path on the remote is being checked for changes.
+    CheckingRemote(String),
+    /// An error was found. Conflicts are reported as
+    /// [`SyncError::BothMoreCurrent`].
+    Error(SyncError),
+    /// A root of the sync directory couldn't be found, so the sync was
+    /// skipped.
+    RootMissing(SyncRoot),
+    /// A file transfer started, finished, or made progress. Contains the
+    /// progress of all the transfers started so far.
+    Transferring(TransferProgress),
+    /// The remote couldn't be reached, so checking and syncing stopped. This
+    /// is reported instead of the errors that caused it. Contains the error
+    /// message from Rclone.
+    Disconnected(String),
+}
+
+/// One of the two roots of a sync directory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncRoot {
+    /// The local path, i.e. a folder on an unmounted drive.
+    Local,
+    /// The remote path, i.e. a folder that got renamed or deleted on the
+    /// server.
+    Remote,
+}
+
+/// The side to keep when resolving a [`SyncError::BothMoreCurrent`] conflict.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Overwrite the remote item with the local one.
+    Local,
+    /// Overwrite the local item with the remote one.
+    Remote,
+    /// Keep both items, by renaming the local one to a conflicted copy (see
+    /// [`conflicted_copy_path`]) and syncing both of them.
+    KeepBoth,
+}
+
+/// The items a sync would delete, as found by
+/// [`SyncEngine::pending_deletions`].
+struct PendingDeletions {
+    items: Vec<SyncItemsModel>,
+    /// The number of remote items a mirror would delete as they were never
+    /// synced, and aren't in the local directory.
+    untracked: usize,
+    /// The number of items being synced in total.
+    total: usize,
+}
+
+impl PendingDeletions {
+    /// Get the number of items that would get deleted.
+    fn count(&self) -> usize {
+        self.items.len() + self.untracked
+    }
+}
+
+/// The listing a sync pass is walking through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pass {
+    Local,
+    Remote,
+}
+
+/// Syncs a single sync directory between the local machine and its remote.
+pub struct SyncEngine {
+    db: DatabaseConnection,
+    remote: RemotesModel,
+    sync_dir: SyncDirsModel,
+    /// When set to `true`, the engine stops at the next item it processes.
+    stop_request: Arc<Mutex<bool>>,
+    on_event: Box<dyn Fn(SyncEvent)>,
+    /// The local/remote item pairs processed during the local pass, so that
+    /// the remote pass doesn't sync them (or report their errors) twice.
+    synced_items: RefCell<HashSet<(String, String)>>,
+    /// The file transfers that are currently running.
+    transfers: RefCell<TransferQueue>,
+    /// The local/remote item pairs to record once the transfers running for
+    /// them (or for anything inside of them) have finished.
+    deferred_records: RefCell<Vec<(String, String)>>,
+    /// The errors from failed transfers that haven't been reported yet.
+    transfer_errors: RefCell<Vec<SyncError>>,
+    /// The hashes of the local files hashed so far, keyed by their path, the
+    /// hash type, and the modification time and size they had at the time.
+    local_hashes: RefCell<HashMap<(String, String, Option<SystemTime>, u64), Option<String>>>,
+    /// Whether anything was transferred, moved or deleted so far.
+    changed: Cell<bool>,
+    /// Whether the remote turned out to be unreachable.
+    disconnected: Cell<bool>,
+    /// Whether an error that affects the whole remote was found, such as the
+    /// login having expired.
+    halted: Cell<bool>,
+    /// The items that failed to sync, and when to try them again.
+    retries: Retries,
+    /// The log of the operations done while syncing.
+    activity: ActivityLog,
+    /// The previous versions of the files overwritten while syncing.
+    versions: Versions,
+    /// The remote subfolders left out of syncing, relative to the roots of the
+    /// sync directory.
+    skipped_dirs: Vec<String>,
+    /// The plan being collected during a dry run by [`Self::plan`], in which
+    /// case nothing gets changed on either side.
+    plan: RefCell<Option<SyncPlan>>,
+}
+
+impl SyncEngine {
+    pub fn new(
+        db: DatabaseConnection,
+        remote: RemotesModel,
+        sync_dir: SyncDirsModel,
+        stop_request: Arc<Mutex<bool>>,
+        on_event: Box<dyn Fn(SyncEvent)>,
+    ) -> Self {
+        let transfers = TransferQueue::new(&remote.name, remote.max_transfers as usize);
+        let retries = Retries::new(db.clone(), sync_dir.id);
+        let activity = ActivityLog::new(db.clone(), sync_dir.id);
+        let versions = Versions::new(&remote.name, sync_dir.clone());
+        let skipped_dirs = skipped_dirs(&db, sync_dir.id);
+
+        Self {
+            db,
+            remote,
+            sync_dir,
+            stop_request,
+            on_event,
+            synced_items: RefCell::new(HashSet::new()),
+            transfers: RefCell::new(transfers),
+            deferred_records: RefCell::new(vec![]),
+            transfer_errors: RefCell::new(vec![]),
+            local_hashes: RefCell::new(HashMap::new()),
+            changed: Cell::new(false),
+            disconnected: Cell::new(false),
+            halted: Cell::new(false),
+            retries,
+            activity,
+            versions,
+            skipped_dirs,
+            plan: RefCell::new(None),
+        }
+    }
+
+    /// Check if the local or remote files differ from the state recorded at
+    /// the last sync, meaning [`Self::sync`] has some work to do.
+    ///
+    /// Only the paths in `local_changes` are checked locally, unless a full
+    /// rescan was requested. The remote is always checked in full, as we have
+    /// no way of getting notified about changes there.
+    pub fn should_sync(&self, local_changes: &LocalChanges) -> bool {
+        // Local file checks.
+        let full_rescan = *local_changes == LocalChanges::Full;
+
+        match local_changes {
+            LocalChanges::Full => {
+                let local_glob = format!("{}/**/*", self.sync_dir.local_path);
+                let Ok(paths) = glob::glob(&local_glob) else {
+                    return true;
+                };
+
+                for maybe_path in paths {
+                    match maybe_path {
+                        Ok(path)
+                            if self.versions.contains_local(&path)
+                                || self.is_skipped(&path.to_string_lossy())
+                                || self.is_local_trash(&path.to_string_lossy())
+                                || !self.local_path_changed(&path) => {}
+                        _ => return true,
+                    }
+                }
+            }
+            LocalChanges::Paths(paths) => {
+                if paths.iter().any(|path| self.is_local_change(path)) {
+                    return true;
+                }
+            }
+        }
+
+        // Remote file checks. If the remote can't be reached there's nothing we can do
+        // until it's back.
+        let items = match rclone::sync::list(
+            &self.remote.name,
+            &self.sync_dir.remote_path,
+            true,
+            RcloneListFilter::All,
+        ) {
+            Ok(items) => items,
+            Err(err) if err.kind() == RcloneErrorKind::Network => {
+                self.disconnect(err.error);
+                return false;
+            }
+            Err(_) => return true,
+        };
+        let mut remote_paths = HashSet::new();
+
+        for item in items {
+            if self.is_remote_trash(&item.path)
+                || self.versions.contains_remote(&item.path)
+                || self.is_skipped(&self.local_path_for(&item.path))
+                || self.is_local_trash(&self.local_path_for(&item.path))
+            {
+                continue;
+            }
+
+            match self.find_db_item(None, Some(&item.path)) {
+                Some(db_item)
+                    if item.mod_time.unix_timestamp() == db_item.last_remote_timestamp as i64 => {}
+                // New remote items are never pulled in upload-only directories.
+                None if self.sync_dir.sync_direction == SyncDirection::UploadOnly => {}
+                _ => return true,
+            }
+
+            remote_paths.insert(item.path);
+        }
+
+        // DB file checks. This covers files that got deleted on the remote (or
+        // locally, during a full rescan), as those changes wouldn't neccesarily be
+        // visible above.
+        let sync_items = util::await_future(
+            SyncItemsEntity::find()
+                .filter(SyncItemsColumn::SyncDirId.eq(self.sync_dir.id))
+                .all(&self.db),
+        )
+        .unwrap();
+
+        for sync_item in sync_items {
+            let remote_exists = remote_paths.contains(&sync_item.remote_path);
+
+            // Local deletions outside of a full rescan were already found through
+            // `local_changes`, so we only need to look at the local file when the
+            // remote one is gone too.
+            if remote_exists && !full_rescan {
+                continue;
+            }
+
+            let local_timestamp = fs::metadata(&sync_item.local_path)
+                .ok()
+                .map(|metadata| get_timestamp(&metadata));
+
+            // If the path doesn't exist both locally and on the remote, then we need to
+            // delete the DB entry.
+            if local_timestamp.is_none() && !remote_exists {
+                util::await_future(sync_item.delete(&self.db)).unwrap();
+                continue;
+            }
+
+            if !remote_exists || local_timestamp != Some(sync_item.last_local_timestamp as u64) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Find out if the local or remote root of the sync directory is missing.
+    ///
+    /// Everything below a missing root looks deleted, so nothing may be synced
+    /// until it's back. A remote that can't be reached isn't counted as
+    /// missing, as we can't tell either way.
+    pub fn missing_root(&self) -> Option<SyncRoot> {
+        if !Path::new(&self.sync_dir.local_path).is_dir() {
+            return Some(SyncRoot::Local);
+        }
+
+        // The root of the remote itself always exists.
+        if util::strip_slashes(&self.sync_dir.remote_path).is_empty() {
+            return None;
+        }
+
+        match rclone::sync::stat(&self.remote.name, &self.sync_dir.remote_path) {
+            Ok(Some(item)) if item.is_dir => None,
+            Ok(_) => Some(SyncRoot::Remote),
+            Err(_) => None,
+        }
+    }
+
+    /// Run a full sync of the directory, first walking through the local
+    /// directory and then through the remote one.
+    ///
+    /// Both roots are checked before each pass, and if one of them is missing,
+    /// the sync stops and a [`SyncEvent::RootMissing`] is reported instead.
+    ///
+    /// If the sync would delete more items than the sync directory's mass
+    /// deletion limits allow, nothing is synced and a
+    /// [`SyncError::MassDeletion`] is reported instead, unless
+    /// `allow_mass_deletion` is set.
+    pub fn sync(&self, allow_mass_deletion: bool) {
+        if self.check_roots() {
+            return;
+        }
+
+        if !allow_mass_deletion {
+            match self.pending_deletions() {
+                Ok(deletions) => {
+                    let total = deletions.total;
+                    let count = deletions.count();
+
+                    if count > self.sync_dir.mass_deletion_count as usize
+                        || (count >= MASS_DELETION_MIN_ITEMS
+                            && count * 100 > total * self.sync_dir.mass_deletion_percent as usize)
+                    {
+                        self.add_error(SyncError::MassDeletion(count, total));
+                        return;
+                    }
+                }
+                Err(err) => {
+                    self.add_error(err);
+                    return;
+                }
+            }
+        }
+
+        self.activity.prune();
+
+        if let Err(err) = self.versions.prune() {
+            self.add_error(err);
+        }
+
+        self.synced_items.borrow_mut().clear();
+        self.sync_local_directory(Path::new(&self.sync_dir.local_path));
+        self.report_transfer_errors();
+
+        if self.should_stop() || self.check_roots() {
+            return;
+        }
+
+        self.sync_remote_directory(&self.sync_dir.remote_path);
+        self.report_transfer_errors();
+    }
+
+    /// Work out what a sync would currently do, without changing anything on
+    /// either side.
+    ///
+    /// Mass deletion limits don't apply, as every deletion is listed in the
+    /// plan anyway.
+    pub fn plan(&self) -> Result<SyncPlan, SyncError> {
+        self.ensure_roots()?;
+        self.plan.replace(Some(SyncPlan::default()));
+        self.synced_items.borrow_mut().clear();
+        self.sync_local_directory(Path::new(&self.sync_dir.local_path));
+
+        if !self.should_stop() {
+            self.sync_remote_directory(&self.sync_dir.remote_path);
+        }
+
+        Ok(self.plan.take().unwrap())
+    }
+
+    /// Whether the engine actually transferred, moved or deleted anything so
+    /// far.
+    pub fn changed(&self) -> bool {
+        self.changed.get()
+    }
+
+    /// Whether this is a dry run started by [`Self::plan`].
+    fn planning(&self) -> bool {
+        self.plan.borrow().is_some()
+    }
+
+    /// Add an item to the plan during a dry run, returning whether one is
+    /// running. Otherwise the caller has to carry out the action itself.
+    fn add_to_plan(&self, action: PlanAction, local_path: &str, is_dir: bool) -> bool {
+        let mut plan = self.plan.borrow_mut();
+        let Some(plan) = plan.as_mut() else {
+            return false;
+        };
+        let path = local_path
+            .strip_prefix(&format!("{}/", self.sync_dir.local_path))
+            .unwrap_or(local_path)
+            .to_owned();
+
+        plan.items.push(PlannedItem {
+            action,
+            path,
+            is_dir,
+        });
+        true
+    }
+
+    /// Report a [`SyncEvent::RootMissing`] if a root of the sync directory is
+    /// missing, returning whether one was.
+    fn check_roots(&self) -> bool {
+        match self.missing_root() {
+            Some(root) => {
+                self.emit(SyncEvent::RootMissing(root));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget the last sync state of the items a sync would currently delete,
+    /// so that the next sync copies them back from the side they still exist
+    /// on instead. Remote items a mirror would delete without ever having
+    /// synced them aren't affected, as they don't belong in the mirror either
+    /// way.
+    pub fn restore_deletions(&self) -> Result<(), SyncError> {
+        for db_item in self.pending_deletions()?.items {
+            util::await_future(db_item.delete(&self.db)).unwrap();
+        }
+
+        Ok(())
+    }
+
+    /// Find the items that were deleted on one side since the last sync, and
+    /// would thus get deleted on the other side by a sync.
+    fn pending_deletions(&self) -> Result<PendingDeletions, SyncError> {
+        // With a root missing, every item below it would look deleted.
+        self.ensure_roots()?;
+
+        let remote_items: HashMap<String, RcloneRemoteItem> = rclone::sync::list(
+            &self.remote.name,
+            &self.sync_dir.remote_path,
+            true,
+            RcloneListFilter::All,
+        )
+        .map_err(|err| SyncError::rclone(&self.sync_dir.remote_path, err))?
+        .into_iter()
+        .map(|item| (item.path.clone(), item))
+        .collect();
+        let sync_items = util::await_future(
+            SyncItemsEntity::find()
+                .filter(SyncItemsColumn::SyncDirId.eq(self.sync_dir.id))
+                .all(&self.db),
+        )
+        .unwrap();
+        let direction = self.sync_dir.sync_direction;
+        let mut local_inodes = None;
+
+        // A mirror deletes the remote items that aren't in the local directory, even
+        // if they were never synced.
+        let untracked = if direction == SyncDirection::Mirror {
+            let tracked_paths: HashSet<&str> = sync_items
+                .iter()
+                .map(|sync_item| sync_item.remote_path.as_str())
+                .collect();
+            let ignore_globs = self.ignore_globs();
+
+            remote_items
+                .keys()
+                .filter(|remote_path| {
+                    !tracked_paths.contains(remote_path.as_str())
+                        && fs::symlink_metadata(self.local_path_for(remote_path)).is_err()
+                        && !self.is_remote_excluded(remote_path, &ignore_globs)
+                })
+                .count()
+        } else {
+            0
+        };
+        let total = sync_items.len() + untracked;
+
+        let items = sync_items
+            .into_iter()
+            .filter(|sync_item| {
+                let local_exists = fs::symlink_metadata(&sync_item.local_path).is_ok();
+                let remote_item = remote_items.get(&sync_item.remote_path);
+
+                match (local_exists, remote_item) {
+                    // Deleted locally, so it'd get deleted on the remote. Items that were only
+                    // moved elsewhere in the directory get moved on the remote instead, and
+                    // ones that changed on the remote since get downloaded again.
+                    (false, Some(remote_item)) => {
+                        let remote_changed = remote_item.mod_time.unix_timestamp()
+                            != sync_item.last_remote_timestamp as i64;
+
+                        direction != SyncDirection::DownloadOnly
+                            && !(direction == SyncDirection::TwoWay && remote_changed)
+                            && !sync_item.local_inode.is_some_and(|inode| {
+                                local_inodes
+                                    .get_or_insert_with(|| self.local_inodes())
+                                    .contains(&(inode as u64))
+                            })
+                    }
+                    // Deleted on the remote, so it'd get deleted locally.
+                    (true, None) => matches!(
+                        direction,
+                        SyncDirection::TwoWay | SyncDirection::DownloadOnly
+                    ),
+                    _ => false,
+                }
+            })
+            .collect();
+
+        Ok(PendingDeletions {
+            items,
+            untracked,
+            total,
+        })
+    }
+
+    /// Resolve a [`SyncError::BothMoreCurrent`] conflict by copying the chosen
+    /// side over the other one (or keeping both of them), and recording the
+    /// result as the last sync state.
+    pub fn resolve_conflict(
+        &self,
+        local_path: &str,
+        remote_path: &str,
+        resolution: ConflictResolution,
+    ) -> Result<(), SyncError> {
+        self.apply_resolution(local_path, remote_path, resolution)?;
+
+        match self.finish_transfers().into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Resolve a conflict like [`Self::resolve_conflict`], without waiting for
+    /// the transfers it starts to finish.
+    fn apply_resolution(
+        &self,
+        local_path: &str,
+        remote_path: &str,
+        resolution: ConflictResolution,
+    ) -> Result<(), SyncError> {
+        let local_is_dir = fs::metadata(local_path)
+            .ok()
+            .map(|metadata| metadata.is_dir());
+        let remote_item = rclone::sync::stat(&self.remote.name, remote_path)
+            .map_err(|err| SyncError::rclone(remote_path, err))?;
+        let local_missing = || {
+            SyncError::General(
+                local_path.to_owned(),
+                RcloneErrorKind::NotFound,
+                tr::tr!("The item doesn't exist locally."),
+            )
+        };
+        let remote_missing = || {
+            SyncError::General(
+                remote_path.to_owned(),
+                RcloneErrorKind::NotFound,
+                tr::tr!("The item doesn't exist on the remote."),
+            )
+        };
+
+        match resolution {
+            ConflictResolution::Local => self.push_local_to_remote(
+                Pass::Local,
+                local_path,
+                remote_path,
+                local_is_dir.ok_or_else(local_missing)?,
+                remote_item.as_ref(),
+            )?,
+            ConflictResolution::Remote => self.pull_remote_to_local(
+                Pass::Remote,
+                local_path,
+                remote_path,
+                local_is_dir,
+                remote_item.as_ref().ok_or_else(remote_missing)?,
+            )?,
+            // Move the local item out of the way into a conflicted copy, and then sync
+            // both it and the remote item.
+            ConflictResolution::KeepBoth => {
+                let local_is_dir = local_is_dir.ok_or_else(local_missing)?;
+                let remote_item = remote_item.ok_or_else(remote_missing)?;
+                let copy_local_path = conflicted_copy_path(local_path);
+                let copy_remote_path = self.remote_path_for(&copy_local_path);
+
+                fs::rename(local_path, &copy_local_path)
+                    .map_err(|err| SyncError::local(local_path, &err))?;
+
+                // The copy gets uploaded here, so the passes mustn't pick it up as a new
+                // local item and upload it again.
+                self.synced_items
+                    .borrow_mut()
+                    .insert((copy_local_path.clone(), copy_remote_path.clone()));
+                self.push_local_to_remote(
+                    Pass::Local,
+                    &copy_local_path,
+                    &copy_remote_path,
+                    local_is_dir,
+                    None,
+                )?;
+                self.record_item(&copy_local_path, &copy_remote_path)?;
+                self.pull_remote_to_local(
+                    Pass::Remote,
+                    local_path,
+                    remote_path,
+                    None,
+                    &remote_item,
+                )?;
+            }
+        }
+
+        self.record_item(local_path, remote_path)
+    }
+
+    /// Send an event to the callback.
+    fn emit(&self, event: SyncEvent) {
+        (self.on_event)(event);
+    }
+
+    /// Report an error to the callback.
+    /// Errors that affect the whole remote stop the sync instead, as every
+    /// following request would fail the same way. Connectivity errors are
+    /// reported as [`SyncEvent::Disconnected`], and the rest only once.
+    ///
+    /// Errors that could go away on their own get retried on later syncs, and
+    /// are only reported once the item keeps failing. Cancelled transfers
+    /// aren't reported at all, and simply get tried again on the next sync.
+    fn add_error(&self, error: SyncError) {
+        // A dry run collects its errors into the plan instead, so they don't count
+        // towards any retries. Errors that affect the whole remote still stop it.
+        if self.planning() {
+            match error {
+                SyncError::BothMoreCurrent(ref local_path, _) => {
+                    let is_dir = Path::new(local_path).is_dir();
+                    self.add_to_plan(PlanAction::Conflict(None), local_path, is_dir);
+                }
+                error => {
+                    if let SyncError::General(_, kind, _) = &error
+                        && kind.affects_remote()
+                    {
+                        self.halted.set(true);
+                    }
+
+                    self.plan.borrow_mut().as_mut().unwrap().errors.push(error);
+                }
+            }
+
+            return;
+        }
+
+        match error {
+            SyncError::General(_, RcloneErrorKind::Network, err) => self.disconnect(err),
+            SyncError::General(_, RcloneErrorKind::Cancelled, _) => (),
+            SyncError::General(_, kind, _) if kind.affects_remote() => {
+                if !self.halted.replace(true) {
+                    self.emit(SyncEvent::Error(error));
+                }
+            }
+            SyncError::General(ref path, kind, ref err)
+                if kind.is_retryable() && !self.retries.failed(path, err) => {}
+            error => self.emit(SyncEvent::Error(error)),
+        }
+    }
+
+    /// Stop syncing because the remote can't be reached, reporting such the
+    /// first time it happens.
+    fn disconnect(&self, error: String) {
+        if !self.disconnected.replace(true) {
+            self.emit(SyncEvent::Disconnected(error));
+        }
+    }
+
+    /// Whether syncing should stop, either because it was requested, because
+    /// the remote can't be reached or used, or because this sync directory was
+    /// paused or removed from the database while we were running. A dry run
+    /// isn't affected by pausing, as it doesn't change anything.
+    fn should_stop(&self) -> bool {
+        if *self.stop_request.lock().unwrap() || self.disconnected.get() || self.halted.get() {
+            return true;
+        }
+
+        match util::await_future(SyncDirsEntity::find_by_id(self.sync_dir.id).one(&self.db))
+            .unwrap()
+        {
+            Some(sync_dir) => sync_dir.paused && !self.planning(),
+            None => true,
+        }
+    }
+
+    /// Get the list of ignore globs for this sync directory.
+    fn ignore_globs(&self) -> Vec<glob::Pattern> {
+        let ignore_file_string = format!("{}/{}", self.sync_dir.local_path, FILE_IGNORE_NAME);
+        let ignore_file_path = Path::new(&ignore_file_string);
+
+        if !ignore_file_path.exists() {
+            return vec![];
+        }
+
+        let _lock = FileLock::lock(
+            ignore_file_path,
+            true,
+            FileOptions::new().write(true).read(true),
+        )
+        .unwrap();
+        let file_content = fs::read_to_string(ignore_file_path).unwrap();
+
+        file_content
+            .lines()
+            .filter_map(|line| glob::Pattern::new(line).ok())
+            .collect()
+    }
+
+    /// Make sure both roots of the sync directory exist, so that a root going
+    /// missing in the middle of a sync can never turn into deletions.
+    fn ensure_roots(&self) -> Result<(), SyncError> {
+        match self.missing_root() {
+            Some(root) => Err(SyncError::General(
+                self.root_path(root),
+                RcloneErrorKind::NotFound,
+                tr::tr!("The sync root couldn't be found."),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the path of one of the sync directory's roots.
+    fn root_path(&self, root: SyncRoot) -> String {
+        match root {
+            SyncRoot::Local => self.sync_dir.local_path.clone(),
+            SyncRoot::Remote => self.sync_dir.remote_path.clone(),
+        }
+    }
+
+    /// Get the path on the remote for a local path in this sync directory.
+    fn remote_path_for(&self, local_path: &str) -> String {
+        let relative_path = local_path
+            .strip_prefix(&format!("{}/", self.sync_dir.local_path))
+            .unwrap();
+        let relative_path = relative_path.strip_suffix('/').unwrap_or(relative_path);
+
+        if self.sync_dir.remote_path.is_empty() {
+            relative_path.to_owned()
+        } else {
+            format!("{}/{relative_path}", self.sync_dir.remote_path)
+        }
+    }
+
+    /// Get the local path for a path on the remote in this sync directory.
+    fn local_path_for(&self, remote_path: &str) -> String {
+        let relative_path = if self.sync_dir.remote_path.is_empty() {
+            remote_path
+        } else {
+            remote_path
+                .strip_prefix(&format!("{}/", self.sync_dir.remote_path))
+                .unwrap()
+        };
+
+        format!("{}/{relative_path}", self.sync_dir.local_path)
+    }
+
+    /// Check if a local path that was reported as changed needs syncing, as
+    /// it's synced at all and differs from the state recorded at the last sync.
+    pub fn is_local_change(&self, path: &Path) -> bool {
+        !self.versions.contains_local(path)
+            && !self.is_skipped(&path.to_string_lossy())
+            && !self.is_local_trash(&path.to_string_lossy())
+            && self.local_path_changed(path)
+    }
+
+    /// Check if a local path differs from the state recorded at the last sync,
+    /// including it having been created or deleted since.
+    fn local_path_changed(&self, path: &Path) -> bool {
+        let db_item = self.find_db_item(Some(&path.display().to_string()), None);
+
+        match (fs::metadata(path), db_item) {
+            (Ok(metadata), Some(db_item)) => {
+                get_timestamp(&metadata) != db_item.last_local_timestamp as u64
+            }
+            (Err(_), None) => false,
+            // New local items are never pushed in download-only directories.
+            (Ok(_), None) => self.sync_dir.sync_direction != SyncDirection::DownloadOnly,
+            _ => true,
+        }
+    }
+
+    /// Find the database record of an item in this sync directory, by its local
+    /// path, remote path, or both.
+    fn find_db_item(
+        &self,
+        local_path: Option<&str>,
+        remote_path: Option<&str>,
+    ) -> Option<SyncItemsModel> {
+        let mut query =
+            SyncItemsEntity::find().filter(SyncItemsColumn::SyncDirId.eq(self.sync_dir.id));
+
+        if let Some(path) = local_path {
+            query = query.filter(SyncItemsColumn::LocalPath.eq(path.to_owned()));
+        }
+        if let Some(path) = remote_path {
+            query = query.filter(SyncItemsColumn::RemotePath.eq(path.to_owned()));
+        }
+
+        util::await_future(query.one(&self.db)).unwrap()
+    }
+
+    /// Record the current state of an item as the state at its last sync.
+    fn record_item(&self, local_path: &str, remote_path: &str) -> Result<(), SyncError> {
+        if self.planning() {
+            return Ok(());
+        }
+
+        // The item isn't in its final state until its transfers are done, so it gets
+        // recorded once they are.
+        if self.transfers.borrow().is_running_at(local_path) {
+            self.deferred_records
+                .borrow_mut()
+                .push((local_path.to_owned(), remote_path.to_owned()));
+            return Ok(());
+        }
+
+        let local_metadata =
+            fs::metadata(local_path).map_err(|err| SyncError::local(local_path, &err))?;
+        let local_timestamp = get_timestamp(&local_metadata);
+        let remote_item = self.stat_remote(remote_path, true)?;
+        let remote_timestamp = remote_item.mod_time.unix_timestamp();
+        let remote_hash = remote_item.hash();
+
+        let mut active_model: SyncItemsActiveModel =
+            match self.find_db_item(Some(local_path), Some(remote_path)) {
+                Some(db_item) => db_item.into(),
+                None => SyncItemsActiveModel {
+                    sync_dir_id: ActiveValue::Set(self.sync_dir.id),
+                    local_path: ActiveValue::Set(local_path.to_owned()),
+                    remote_path: ActiveValue::Set(remote_path.to_owned()),
+                    ..Default::default()
+                },
+            };
+        active_model.last_local_timestamp = ActiveValue::Set(local_timestamp.try_into().unwrap());
+        active_model.last_remote_timestamp = ActiveValue::Set(remote_timestamp.try_into().unwrap());
+        active_model.last_size =
+            ActiveValue::Set((!remote_item.is_dir).then_some(remote_item.size));
+        active_model.hash_type =
+            ActiveValue::Set(remote_hash.map(|(hash_type, _)| hash_type.to_owned()));
+        active_model.last_hash = ActiveValue::Set(remote_hash.map(|(_, hash)| hash.to_owned()));
+        active_model.local_inode = ActiveValue::Set(Some(local_metadata.ino() as i64));
+        util::await_future(active_model.save(&self.db)).unwrap();
+        self.retries.clear(&[local_path, remote_path]);
+
+        Ok(())
+    }
+
+    /// Check if a local file still has the content recorded at its last sync.
+    fn local_content_unchanged(
+        &self,
+        local_path: &str,
+        local_metadata: &Metadata,
+        db_item: &SyncItemsModel,
+    ) -> bool {
+        let (Some(size), Some(hash_type), Some(hash)) =
+            (db_item.last_size, &db_item.hash_type, &db_item.last_hash)
+        else {
+            return false;
+        };
+
+        local_metadata.is_file()
+            && local_metadata.len() as i64 == size
+            && self.local_hash(local_path, hash_type).as_ref() == Some(hash)
+    }
+
+    /// Check if a remote file still has the content recorded at its last sync.
+    fn remote_content_unchanged(
+        &self,
+        remote_item: &RcloneRemoteItem,
+        db_item: &SyncItemsModel,
+    ) -> bool {
+        let (Some(size), Some(hash_type), Some(hash)) =
+            (db_item.last_size, &db_item.hash_type, &db_item.last_hash)
+        else {
+            return false;
+        };
+
+        !remote_item.is_dir
+            && remote_item.size == size
+            && self.remote_hashes(&remote_item.path).get(hash_type) == Some(hash)
+    }
+
+    /// Check if a local and a remote file have the same content, in which case
+    /// they're already in sync.
+    fn contents_match(
+        &self,
+        local_path: &str,
+        local_metadata: &Metadata,
+        remote_item: &RcloneRemoteItem,
+    ) -> bool {
+        if !local_metadata.is_file()
+            || remote_item.is_dir
+            || local_metadata.len() as i64 != remote_item.size
+        {
+            return false;
+        }
+
+        // Without a hash to compare we can't tell, even if the sizes match.
+        let remote_hashes = self.remote_hashes(&remote_item.path);
+        let Some((hash_type, hash)) = remote_hashes.iter().next() else {
+            return false;
+        };
+
+        self.local_hash(local_path, hash_type)
+            .is_some_and(|local_hash| local_hash == *hash)
+    }
+
+    /// Get the hash of a local file's content with the given hash type. A file
+    /// only gets read once while it stays the same, as it can be checked more
+    /// than once while syncing.
+    fn local_hash(&self, local_path: &str, hash_type: &str) -> Option<String> {
+        let metadata = fs::metadata(local_path).ok()?;
+        let key = (
+            local_path.to_owned(),
+            hash_type.to_owned(),
+            metadata.modified().ok(),
+            metadata.len(),
+        );
+
+        if let Some(hash) = self.local_hashes.borrow().get(&key) {
+            return hash.clone();
+        }
+
+        let hash = rclone::sync::local_hash(local_path, hash_type)
+            .ok()
+            .flatten();
+        self.local_hashes.borrow_mut().insert(key, hash.clone());
+        hash
+    }
+
+    /// Get the hashes of a remote file's content. Listings don't include them,
+    /// as they take extra work on some remotes, so this should only be called
+    /// once the sizes match and a hash is needed to tell the files apart.
+    fn remote_hashes(&self, remote_path: &str) -> BTreeMap<String, String> {
+        rclone::sync::stat_with_hashes(&self.remote.name, remote_path)
+            .ok()
+            .flatten()
+            .map(|item| item.hashes)
+            .unwrap_or_default()
+    }
+
+    /// Delete the database record of an item.
+    fn delete_db_item(&self, local_path: &str, remote_path: &str) {
+        if self.planning() {
+            return;
+        }
+
+        if let Some(db_item) = self.find_db_item(Some(local_path), Some(remote_path)) {
+            util::await_future(db_item.delete(&self.db)).unwrap();
+        }
+    }
+
+    /// Get the inode numbers of everything in the local directory.
+    fn local_inodes(&self) -> HashSet<u64> {
+        let local_glob = format!("{}/**/*", self.sync_dir.local_path);
+        let Ok(paths) = glob::glob(&local_glob) else {
+            return HashSet::new();
+        };
+
+        paths
+            .filter_map(|path| fs::metadata(path.ok()?).ok())
+            .map(|metadata| metadata.ino())
+            .collect()
+    }
+
+    /// Find the database record of a synced item that got moved or renamed to
+    /// the local item at `local_path`.
+    ///
+    /// Items are matched by their inode number, or for files, by their size and
+    /// content. A file matched by its inode also has to be unchanged since the
+    /// last sync, as an inode can get reused, and the move would otherwise skip
+    /// uploading the new content. The old local item has to be gone, as the new
+    /// one is a copy otherwise, and the remote item has to be unchanged since
+    /// the last sync, so it can be moved along with it.
+    fn find_move_source(
+        &self,
+        local_path: &str,
+        local_metadata: &Metadata,
+    ) -> Option<SyncItemsModel> {
+        // The cheap checks go first, so candidates don't get hashed or looked up on the
+        // remote needlessly.
+        let is_gone = |db_item: &SyncItemsModel| {
+            fs::symlink_metadata(&db_item.local_path).is_err()
+                && db_item.last_size.is_none() == local_metadata.is_dir()
+        };
+        let content_matches = |db_item: &SyncItemsModel| {
+            let (Some(hash_type), Some(last_hash)) = (&db_item.hash_type, &db_item.last_hash)
+            else {
+                return false;
+            };
+
+            self.local_hash(local_path, hash_type)
+                .is_some_and(|hash| hash == *last_hash)
+        };
+        let remote_unchanged = |db_item: &SyncItemsModel| {
+            rclone::sync::stat(&self.remote.name, &db_item.remote_path)
+                .ok()
+                .flatten()
+                .is_some_and(|item| {
+                    item.mod_time.unix_timestamp() == db_item.last_remote_timestamp as i64
+                })
+        };
+        let query =
+            || SyncItemsEntity::find().filter(SyncItemsColumn::SyncDirId.eq(self.sync_dir.id));
+
+        let by_inode = util::await_future(
+            query()
+                .filter(SyncItemsColumn::LocalInode.eq(local_metadata.ino() as i64))
+                .all(&self.db),
+        )
+        .unwrap()
+        .into_iter()
+        .find(|db_item| {
+            let unchanged = local_metadata.is_dir()
+                || (db_item.last_size == Some(local_metadata.len() as i64)
+                    && (get_timestamp(local_metadata) == db_item.last_local_timestamp as u64
+                        || content_matches(db_item)));
+
+            is_gone(db_item) && unchanged && remote_unchanged(db_item)
+        });
+
+        if by_inode.is_some() || !local_metadata.is_file() {
+            return by_inode;
+        }
+
+        // The inode changes when a file gets moved to another filesystem, or gets
+        // saved by writing a new file in its place, so fall back to its content.
+        util::await_future(
+            query()
+                .filter(SyncItemsColumn::LastSize.eq(local_metadata.len() as i64))
+                .filter(SyncItemsColumn::LastHash.is_not_null())
+                .all(&self.db),
+        )
+        .unwrap()
+        .into_iter()
+        .find(|db_item| is_gone(db_item) && content_matches(db_item) && remote_unchanged(db_item))
+    }
+
+    /// Move an item on the remote to follow it being moved or renamed locally,
+    /// and update the database records of it and everything inside of it to
+    /// their new paths.
+    fn move_remote_item(
+        &self,
+        pass: Pass,
+        source: &SyncItemsModel,
+        local_path: &str,
+        remote_path: &str,
+        is_dir: bool,
+    ) -> Result<(), SyncError> {
+        if self.add_to_plan(PlanAction::Move, local_path, is_dir) {
+            return Ok(());
+        }
+
+        let result = if is_dir {
+            rclone::sync::move_dir(&self.remote.name, &source.remote_path, remote_path)
+        } else {
+            rclone::sync::move_file(&self.remote.name, &source.remote_path, remote_path)
+        }
+        .map_err(|err| SyncError::rclone(&source.remote_path, err));
+        self.activity
+            .record(SyncAction::Move, local_path, None, result.as_ref().copied());
+        result?;
+        self.changed.set(true);
+
+        let db_items = util::await_future(
+            SyncItemsEntity::find()
+                .filter(SyncItemsColumn::SyncDirId.eq(self.sync_dir.id))
+                .filter(SyncItemsColumn::LocalPath.starts_with(&format!("{}/", source.local_path)))
+                .all(&self.db),
+        )
+        .unwrap();
+
+        for db_item in db_items.into_iter().chain([source.clone()]) {
+            let new_local_path = format!(
+                "{local_path}{}",
+                db_item.local_path.strip_prefix(&source.local_path).unwrap()
+            );
+            let new_remote_path = format!(
+                "{remote_path}{}",
+                db_item
+                    .remote_path
+                    .strip_prefix(&source.remote_path)
+                    .unwrap()
+            );
+            self.retries
+                .clear(&[db_item.local_path.as_str(), db_item.remote_path.as_str()]);
+
+            let mut active_model: SyncItemsActiveModel = db_item.into();
+            active_model.local_path = ActiveValue::Set(new_local_path);
+            active_model.remote_path = ActiveValue::Set(new_remote_path);
+            util::await_future(active_model.update(&self.db)).unwrap();
+        }
+
+        // Anything inside of a directory may have changed along with the move.
+        if is_dir {
+            self.recurse(pass, local_path, remote_path);
+        }
+
+        self.record_item(local_path, remote_path)
+    }
+
+    /// Get an item on the remote, treating a missing item as an error.
+    fn stat_remote(
+        &self,
+        remote_path: &str,
+        with_hashes: bool,
+    ) -> Result<RcloneRemoteItem, SyncError> {
+        let item = if with_hashes {
+            rclone::sync::stat_with_hashes(&self.remote.name, remote_path)
+        } else {
+            rclone::sync::stat(&self.remote.name, remote_path)
+        };
+
+        match item {
+            Ok(Some(item)) => Ok(item),
+            Ok(None) => Err(SyncError::General(
+                remote_path.to_owned(),
+                RcloneErrorKind::NotFound,
+                tr::tr!("The item doesn't exist on the remote."),
+            )),
+            Err(err) => Err(SyncError::rclone(remote_path, err)),
+        }
+    }
+
+    /// Delete a local file or directory, by moving it to the user's trash.
+    fn delete_local_item(&self, local_path: &str) -> Result<(), SyncError> {
+        self.ensure_roots()?;
+
+        if self.add_to_plan(
+            PlanAction::DeleteLocal,
+            local_path,
+            Path::new(local_path).is_dir(),
+        ) {
+            return Ok(());
+        }
+
+        let result = trash::trash_local(Path::new(local_path))
+            .map_err(|err| SyncError::local(local_path, &err));
+        self.activity.record(
+            SyncAction::DeleteLocal,
+            local_path,
+            None,
+            result.as_ref().copied(),
+        );
+        if result.is_ok() {
+            self.changed.set(true);
+        }
+        result
+    }
+
+    /// Delete a file or directory on the remote, by moving it to the sync
+    /// directory's remote trash. If no remote trash is set, the item is deleted
+    /// for good.
+    fn delete_remote_item(&self, remote_path: &str, is_dir: bool) -> Result<(), SyncError> {
+        self.ensure_roots()?;
+
+        if self.add_to_plan(
+            PlanAction::DeleteRemote,
+            &self.local_path_for(remote_path),
+            is_dir,
+        ) {
+            return Ok(());
+        }
+
+        let result = if !self.sync_dir.remote_trash_path.is_empty() {
+            trash::trash_remote(
+                &self.remote.name,
+                &self.sync_dir.remote_trash_path,
+                remote_path,
+                is_dir,
+            )
+        } else if is_dir {
+            rclone::sync::purge(&self.remote.name, remote_path)
+        } else {
+            rclone::sync::delete(&self.remote.name, remote_path)
+        }
+        .map_err(|err| SyncError::rclone(remote_path, err));
+        self.activity.record(
+            SyncAction::DeleteRemote,
+            &self.local_path_for(remote_path),
+            None,
+            result.as_ref().copied(),
+        );
+        if result.is_ok() {
+            self.changed.set(true);
+        }
+        result
+    }
+
+    /// Check if a path on the remote is inside of the sync directory's remote
+    /// trash, in which case it shouldn't be synced.
+    fn is_remote_trash(&self, remote_path: &str) -> bool {
+        let trash_path = util::strip_slashes(&self.sync_dir.remote_trash_path);
+
+        !trash_path.is_empty()
+            && (remote_path == trash_path || remote_path.starts_with(&format!("{trash_path}/")))
+    }
+
+    /// Check if a local path is inside a remote subfolder that was left out of
+    /// syncing through selective sync.
+    fn is_skipped(&self, local_path: &str) -> bool {
+        let Some(relative_path) =
+            local_path.strip_prefix(&format!("{}/", self.sync_dir.local_path))
+        else {
+            return false;
+        };
+
+        self.skipped_dirs
+            .iter()
+            .any(|dir| is_inside(relative_path, dir))
+    }
+
+    /// Check if a local path is inside the trash directory at the top of a
+    /// filesystem (see [`trash::trash_local`]). When the sync directory or one
+    /// of its subfolders is a mount point, that trash ends up inside of it.
+    fn is_local_trash(&self, local_path: &str) -> bool {
+        let trash_name = trash::topdir_trash_name();
+
+        local_path
+            .strip_prefix(&format!("{}/", self.sync_dir.local_path))
+            .is_some_and(|relative_path| {
+                relative_path
+                    .split('/')
+                    .any(|component| component == trash_name)
+            })
+    }
+
+    /// Check if syncing leaves the remote item at `remote_path` alone, as it or
+    /// one of the directories it's in matches the ignore list, is in the remote
+    /// or local trash, holds previous versions or was left out through
+    /// selective sync.
+    fn is_remote_excluded(&self, remote_path: &str, ignore_globs: &[glob::Pattern]) -> bool {
+        let mut path = remote_path;
+
+        loop {
+            if ignore_globs.iter().any(|pattern| pattern.matches(path))
+                || self.is_remote_trash(path)
+                || self.versions.contains_remote(path)
+                || self.is_skipped(&self.local_path_for(path))
+                || self.is_local_trash(&self.local_path_for(path))
+            {
+                return true;
+            }
+
+            match path.rsplit_once('/') {
+                Some((parent, _)) if parent.len() > self.sync_dir.remote_path.len() => {
+                    path = parent
+                }
+                _ => return false,
+            }
+        }
+    }
+
+    /// Sync a local directory, recursing into any directories inside of it.
+    fn sync_local_directory(&self, local_dir: &Path) {
+        let dir_string = local_dir.to_str().unwrap().to_owned();
+        self.emit(SyncEvent::CheckingLocal(dir_string.clone()));
+
+        let directory = match fs::read_dir(local_dir) {
+            Ok(directory) => directory,
+            Err(err) => {
+                self.add_error(SyncError::local(&dir_string, &err));
+                return;
+            }
+        };
+        let ignore_globs = self.ignore_globs();
+
+        for item in directory {
+            if self.should_stop() {
+                break;
+            }
+
+            let item = match item {
+                Ok(item) => item,
+                Err(err) => {
+                    self.add_error(SyncError::local(&dir_string, &err));
+                    continue;
+                }
+            };
+            let local_path = item.path().to_str().unwrap().to_owned();
+            let remote_path = self.remote_path_for(&local_path);
+            // The above path, with `sync_dir.remote_path` stripped from it.
+            let stripped_remote_path =
+                if remote_path.contains('/') && self.sync_dir.remote_path.contains('/') {
+                    remote_path
+                        .strip_prefix(&format!("{}/", self.sync_dir.remote_path))
+                        .unwrap()
+                        .to_owned()
+                } else {
+                    remote_path.clone()
+                };
+
+            self.emit(SyncEvent::CheckingLocal(local_path.clone()));
+
+            // If this item matches the ignore list, would end up in the remote trash, is in
+            // a local trash, holds previous versions or was left out through selective
+            // sync, don't sync it.
+            if ignore_globs
+                .iter()
+                .any(|pattern| pattern.matches(&stripped_remote_path))
+                || self.is_remote_trash(&remote_path)
+                || self.versions.contains_remote(&remote_path)
+                || self.is_skipped(&local_path)
+                || self.is_local_trash(&local_path)
+            {
+                continue;
+            }
+
+            // If this item was already synced, i.e. as the conflicted copy from resolving a
+            // conflict, don't sync it again.
+            if !self
+                .synced_items
+                .borrow_mut()
+                .insert((local_path.clone(), remote_path.clone()))
+            {
+                continue;
+            }
+
+            let remote_item = match rclone::sync::stat(&self.remote.name, &remote_path) {
+                Ok(remote_item) => remote_item,
+                Err(err) => {
+                    self.add_error(SyncError::rclone(&remote_path, err));
+                    continue;
+                }
+            };
+
+            if let Err(err) = self.sync_item(Pass::Local, &local_path, &remote_path, remote_item) {
+                self.add_error(err);
+            }
+        }
+    }
+
+    /// Sync a remote directory, recursing into any directories inside of it.
+    /// `remote_dir` should be the full path from the root of the remote, with
+    /// any `/` prefix or suffix removed.
+    fn sync_remote_directory(&self, remote_dir: &str) {
+        self.emit(SyncEvent::CheckingRemote(remote_dir.to_owned()));
+
+        let items =
+            match rclone::sync::list(&self.remote.name, remote_dir, false, RcloneListFilter::All) {
+                Ok(items) => items,
+                Err(err) => {
+                    self.add_error(SyncError::rclone(remote_dir, err));
+                    return;
+                }
+            };
+        let ignore_globs = self.ignore_globs();
+
+        for item in items {
+            if self.should_stop() {
+                break;
+            }
+
+            let remote_path = item.path.clone();
+            let local_path = self.local_path_for(&remote_path);
+
+            // If this item matches the ignore filter, is in either trash, holds previous
+            // versions or was left out through selective sync, don't sync it.
+            if ignore_globs
+                .iter()
+                .any(|pattern| pattern.matches(&item.path))
+                || self.is_remote_trash(&item.path)
+                || self.versions.contains_remote(&item.path)
+                || self.is_skipped(&local_path)
+                || self.is_local_trash(&local_path)
+            {
+                continue;
+            }
+
+            self.emit(SyncEvent::CheckingRemote(remote_path.clone()));
+
+            // If we've already synced this item during the local pass, don't sync it
+            // again.
+            if self
+                .synced_items
+                .borrow()
+                .contains(&(local_path.clone(), remote_path.clone()))
+            {
+                continue;
+            }
+
+            if let Err(err) = self.sync_item(Pass::Remote, &local_path, &remote_path, Some(item)) {
+                self.add_error(err);
+            }
+        }
+    }
+
+    /// Sync a single item, comparing both sides against the state recorded at
+    /// the last sync.
+    fn sync_item(
+        &self,
+        pass: Pass,
+        local_path: &str,
+        remote_path: &str,
+        remote_item: Option<RcloneRemoteItem>,
+    ) -> Result<(), SyncError> {
+        // The item failed to sync recently, so leave it alone until it's due to be
+        // tried again.
+        // A dry run lists it anyway, as it'll get synced eventually.
+        if !self.planning()
+            && (self.retries.is_waiting(local_path) || self.retries.is_waiting(remote_path))
+        {
+            return Ok(());
+        }
+
+        let local_metadata = fs::metadata(local_path).ok();
+        let local_timestamp = local_metadata.as_ref().map(get_timestamp);
+        let local_is_dir = local_metadata.as_ref().map(|metadata| metadata.is_dir());
+        let remote_timestamp = remote_item
+            .as_ref()
+            .map(|item| item.mod_time.unix_timestamp());
+
+        // The user cancelled the item's transfer, so leave it alone until it changes
+        // again.
+        if !self.planning()
+            && (self
+                .retries
+                .is_held(local_path, local_timestamp.map(|time| time as i64))
+                || self.retries.is_held(remote_path, remote_timestamp))
+        {
+            return Ok(());
+        }
+
+        // If the item exists on both sides with the same content, there's nothing to
+        // transfer, no matter what the timestamps say. Just make sure the database
+        // reflects the current timestamps.
+        let db_item = self.find_db_item(Some(local_path), Some(remote_path));
+        let timestamps_match = db_item.as_ref().is_some_and(|db_item| {
+            local_timestamp == Some(db_item.last_local_timestamp as u64)
+                && remote_timestamp == Some(db_item.last_remote_timestamp as i64)
+        });
+
+        if !timestamps_match
+            && let (Some(metadata), Some(item)) = (&local_metadata, &remote_item)
+            && self.contents_match(local_path, metadata, item)
+        {
+            return self.record_item(local_path, remote_path);
+        }
+
+        let direction = self.sync_dir.sync_direction;
+
+        // If we have a record of the last sync, use that to see what changed. An item
+        // whose timestamp changed but whose content didn't (i.e. from being touched or
+        // restored from a backup) doesn't count as changed.
+        if let Some(db_item) = db_item {
+            let mut local_changed = local_timestamp
+                .is_some_and(|time| time != db_item.last_local_timestamp as u64)
+                && !self.local_content_unchanged(
+                    local_path,
+                    local_metadata.as_ref().unwrap(),
+                    &db_item,
+                );
+            let mut remote_changed = remote_timestamp
+                .is_some_and(|time| time != db_item.last_remote_timestamp as i64)
+                && !self.remote_content_unchanged(remote_item.as_ref().unwrap(), &db_item);
+
+            // A mirror reverts any changes made on the remote.
+            if direction == SyncDirection::Mirror && remote_changed {
+                match local_is_dir {
+                    Some(is_dir) => {
+                        self.push_local_to_remote(
+                            pass,
+                            local_path,
+                            remote_path,
+                            is_dir,
+                            remote_item.as_ref(),
+                        )?;
+                        self.record_item(local_path, remote_path)?;
+                    }
+                    None => {
+                        self.delete_remote_item(remote_path, remote_item.unwrap().is_dir)?;
+                        self.delete_db_item(local_path, remote_path);
+                    }
+                }
+
+                return Ok(());
+            }
+
+            // Changes on a side we don't sync from are ignored. The new state still gets
+            // recorded below, so they don't keep getting checked.
+            match direction {
+                SyncDirection::UploadOnly => remote_changed = false,
+                SyncDirection::DownloadOnly => local_changed = false,
+                SyncDirection::TwoWay | SyncDirection::Mirror => (),
+            }
+
+            // Both items changed since the last sync. If they're both directories it's
+            // probably just because something inside of them changed, so check their
+            // contents. Otherwise the sync directory's conflict policy decides which one
+            // to keep, or the user does if there's none.
+            if local_changed && remote_changed {
+                if local_is_dir == Some(true) && remote_item.as_ref().unwrap().is_dir {
+                    self.recurse(pass, local_path, remote_path);
+                    self.record_item(local_path, remote_path)?;
+                } else {
+                    let newest = if local_timestamp.unwrap() > remote_timestamp.unwrap() as u64 {
+                        ConflictResolution::Local
+                    } else {
+                        ConflictResolution::Remote
+                    };
+                    let resolution = match self.sync_dir.conflict_policy {
+                        ConflictPolicy::Ask => {
+                            return Err(SyncError::BothMoreCurrent(
+                                local_path.to_owned(),
+                                remote_path.to_owned(),
+                            ))
+                        }
+                        ConflictPolicy::PreferLocal => ConflictResolution::Local,
+                        ConflictPolicy::PreferRemote => ConflictResolution::Remote,
+                        ConflictPolicy::NewestWins => newest,
+                        ConflictPolicy::KeepBoth => ConflictResolution::KeepBoth,
+                    };
+
+                    if self.add_to_plan(
+                        PlanAction::Conflict(Some(resolution)),
+                        local_path,
+                        local_is_dir.unwrap(),
+                    ) {
+                        return Ok(());
+                    }
+
+                    self.apply_resolution(local_path, remote_path, resolution)?;
+                }
+            // The local item is more recent.
+            } else if local_changed {
+                self.push_local_to_remote(
+                    pass,
+                    local_path,
+                    remote_path,
+                    local_is_dir.unwrap(),
+                    remote_item.as_ref(),
+                )?;
+                self.record_item(local_path, remote_path)?;
+            // The remote item is more recent.
+            } else if remote_changed {
+                self.pull_remote_to_local(
+                    pass,
+                    local_path,
+                    remote_path,
+                    local_is_dir,
+                    remote_item.as_ref().unwrap(),
+                )?;
+                self.record_item(local_path, remote_path)?;
+            } else {
+                match (local_is_dir, &remote_item) {
+                    // The item is gone from both sides, so just forget about it.
+                    (None, None) => self.delete_db_item(local_path, remote_path),
+                    // The item got deleted on the remote and is unchanged locally, so reflect
+                    // such locally, unless we never delete locally, in which case it gets
+                    // restored on the remote.
+                    (Some(is_dir), None) => match direction {
+                        SyncDirection::TwoWay | SyncDirection::DownloadOnly => {
+                            self.delete_local_item(local_path)?;
+                            self.delete_db_item(local_path, remote_path);
+                        }
+                        SyncDirection::UploadOnly | SyncDirection::Mirror => {
+                            self.push_local_to_remote(pass, local_path, remote_path, is_dir, None)?;
+                            self.record_item(local_path, remote_path)?;
+                        }
+                    },
+                    // The item got deleted locally and is unchanged on the remote, so reflect
+                    // such on the remote, unless we never push, in which case it gets restored
+                    // locally.
+                    (None, Some(item)) => match direction {
+                        SyncDirection::DownloadOnly => {
+                            self.pull_remote_to_local(pass, local_path, remote_path, None, item)?;
+                            self.record_item(local_path, remote_path)?;
+                        }
+                        SyncDirection::TwoWay
+                        | SyncDirection::UploadOnly
+                        | SyncDirection::Mirror => {
+                            self.delete_remote_item(remote_path, item.is_dir)?;
+                            self.delete_db_item(local_path, remote_path);
+                        }
+                    },
+                    // Both items remain unchanged. Directories still need their contents
+                    // checked, as a directory's timestamp doesn't change when a file inside
+                    // of it gets modified.
+                    (Some(is_dir), Some(item)) => {
+                        if is_dir && item.is_dir {
+                            self.recurse(pass, local_path, remote_path);
+                        }
+
+                        // Only the timestamps changed, so record the new ones to avoid checking
+                        // the content again next time.
+                        if !timestamps_match {
+                            self.record_item(local_path, remote_path)?;
+                        }
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+
+        // Otherwise this is the first time we've seen the item, so keep whichever side
+        // is the most recent (or the side we sync from) and record our new transaction
+        // in the database.
+        match (local_timestamp, remote_timestamp) {
+            (Some(l_timestamp), Some(r_timestamp)) => {
+                let keep_local = match direction {
+                    SyncDirection::TwoWay => l_timestamp > r_timestamp as u64,
+                    SyncDirection::UploadOnly | SyncDirection::Mirror => true,
+                    SyncDirection::DownloadOnly => false,
+                };
+
+                if keep_local {
+                    self.push_local_to_remote(
+                        pass,
+                        local_path,
+                        remote_path,
+                        local_is_dir.unwrap(),
+                        remote_item.as_ref(),
+                    )?;
+                } else {
+                    self.pull_remote_to_local(
+                        pass,
+                        local_path,
+                        remote_path,
+                        local_is_dir,
+                        remote_item.as_ref().unwrap(),
+                    )?;
+                }
+            }
+            (Some(_), None) => {
+                if direction == SyncDirection::DownloadOnly {
+                    return Ok(());
+                }
+
+                // The item may just have been moved or renamed, in which case it gets moved
+                // on the remote too instead of being uploaded all over again.
+                if let Some(source) =
+                    self.find_move_source(local_path, local_metadata.as_ref().unwrap())
+                {
+                    return self.move_remote_item(
+                        pass,
+                        &source,
+                        local_path,
+                        remote_path,
+                        local_is_dir.unwrap(),
+                    );
+                }
+
+                self.push_local_to_remote(
+                    pass,
+                    local_path,
+                    remote_path,
+                    local_is_dir.unwrap(),
+                    None,
+                )?
+            }
+            (None, Some(_)) => match direction {
+                SyncDirection::TwoWay | SyncDirection::DownloadOnly => self.pull_remote_to_local(
+                    pass,
+                    local_path,
+                    remote_path,
+                    None,
+                    remote_item.as_ref().unwrap(),
+                )?,
+                SyncDirection::UploadOnly => return Ok(()),
+                // The item isn't in the local directory, so it doesn't belong in the mirror.
+                SyncDirection::Mirror => {
+                    return self.delete_remote_item(remote_path, remote_item.unwrap().is_dir)
+                }
+            },
+            (None, None) => return Ok(()),
+        }
+
+        self.record_item(local_path, remote_path)
+    }
+
+    /// Sync the contents of a directory that exists on both sides, through the
+    /// listing of the current pass.
+    fn recurse(&self, pass: Pass, local_path: &str, remote_path: &str) {
+        match pass {
+            Pass::Local => {
+                self.sync_local_directory(Path::new(local_path));
+                self.emit(SyncEvent::CheckingLocal(local_path.to_owned()));
+            }
+            Pass::Remote => {
+                self.sync_remote_directory(remote_path);
+                self.emit(SyncEvent::CheckingRemote(remote_path.to_owned()));
+            }
+        }
+    }
+
+    /// Start a file transfer, after waiting for a running one to finish if the
+    /// remote's transfer limit has been reached.
+    fn queue_transfer(&self, transfer: Transfer) {
+        while self.transfers.borrow().is_full() {
+            self.wait_for_transfer();
+        }
+
+        self.transfers.borrow_mut().start(transfer);
+        self.emit(SyncEvent::Transferring(self.transfers.borrow().progress()));
+    }
+
+    /// Wait for a running transfer to finish, and record the items that are no
+    /// longer waiting on any transfers. The progress of the running transfers
+    /// gets reported in the meantime.
+    fn wait_for_transfer(&self) {
+        let receiver = self.transfers.borrow().receiver();
+        let (transfer, result) = loop {
+            let receiver = receiver.clone();
+
+            // The queue keeps its sender around, so this can only time out.
+            match util::run_in_background(move || {
+                receiver
+                    .lock()
+                    .unwrap()
+                    .recv_timeout(TRANSFER_PROGRESS_INTERVAL)
+            }) {
+                Ok(finished) => break finished,
+                Err(_) => {
+                    self.emit(SyncEvent::Transferring(self.transfers.borrow().progress()));
+
+                    // Don't keep a large transfer running until it's done when syncing was asked
+                    // to stop.
+                    if self.should_stop() {
+                        self.transfers.borrow().cancel_all();
+                    }
+                }
+            }
+        };
+        self.transfers.borrow_mut().finish(&transfer);
+        let action = match transfer.kind {
+            TransferKind::Upload => SyncAction::Upload,
+            TransferKind::Download => SyncAction::Download,
+        };
+
+        // A failed transfer mustn't be recorded as synced, so it gets retried on the
+        // next sync.
+        if let Err(err) = result {
+            self.deferred_records
+                .borrow_mut()
+                .retain(|(local_path, remote_path)| {
+                    local_path != &transfer.local_path || remote_path != &transfer.remote_path
+                });
+            let path = match transfer.kind {
+                TransferKind::Upload => &transfer.local_path,
+                TransferKind::Download => &transfer.remote_path,
+            };
+            let err = SyncError::rclone(path, err);
+
+            // A transfer cancelled while syncing wasn't asked to stop was cancelled by the
+            // user, which should stick until the item changes again.
+            if matches!(err, SyncError::General(_, RcloneErrorKind::Cancelled, _))
+                && !self.should_stop()
+            {
+                self.hold_transfer(&transfer);
+            }
+
+            self.activity
+                .record(action, &transfer.local_path, None, Err(&err));
+            self.transfer_errors.borrow_mut().push(err);
+        } else {
+            self.changed.set(true);
+            self.activity
+                .record(action, &transfer.local_path, Some(transfer.size), Ok(()));
+        }
+
+        let ready: Vec<(String, String)> = {
+            let transfers = self.transfers.borrow();
+            let mut deferred_records = self.deferred_records.borrow_mut();
+            let (ready, waiting) = deferred_records
+                .drain(..)
+                .partition(|(local_path, _)| !transfers.is_running_at(local_path));
+            *deferred_records = waiting;
+            ready
+        };
+
+        for (local_path, remote_path) in ready {
+            if let Err(err) = self.record_item(&local_path, &remote_path) {
+                self.transfer_errors.borrow_mut().push(err);
+            }
+        }
+
+        self.emit(SyncEvent::Transferring(self.transfers.borrow().progress()));
+    }
+
+    /// Leave the source item of a cancelled transfer alone until it changes
+    /// again.
+    fn hold_transfer(&self, transfer: &Transfer) {
+        let timestamp = match transfer.kind {
+            TransferKind::Upload => fs::metadata(&transfer.local_path)
+                .ok()
+                .map(|metadata| get_timestamp(&metadata) as i64),
+            TransferKind::Download => rclone::sync::stat(&self.remote.name, &transfer.remote_path)
+                .ok()
+                .flatten()
+                .map(|item| item.mod_time.unix_timestamp()),
+        };
+        let path = match transfer.kind {
+            TransferKind::Upload => &transfer.local_path,
+            TransferKind::Download => &transfer.remote_path,
+        };
+
+        if let Some(timestamp) = timestamp {
+            self.retries.hold(path, timestamp);
+        }
+    }
+
+    /// Wait for all running transfers to finish, returning the errors from the
+    /// ones that failed.
+    fn finish_transfers(&self) -> Vec<SyncError> {
+        while !self.transfers.borrow().is_empty() {
+            self.wait_for_transfer();
+        }
+
+        self.transfer_errors.take()
+    }
+
+    /// Wait for all running transfers to finish, and report the errors from
+    /// the ones that failed.
+    fn report_transfer_errors(&self) {
+        for err in self.finish_transfers() {
+            self.add_error(err);
+        }
+    }
+
+    /// Push a local item to the remote, replacing whatever is currently there.
+    fn push_local_to_remote(
+        &self,
+        pass: Pass,
+        local_path: &str,
+        remote_path: &str,
+        local_is_dir: bool,
+        remote_item: Option<&RcloneRemoteItem>,
+    ) -> Result<(), SyncError> {
+        // Make sure the file types match up.
+        let remote_is_dir = match remote_item {
+            Some(item) if item.is_dir != local_is_dir => {
+                if !self.add_to_plan(
+                    PlanAction::TypeChange(SyncRoot::Remote),
+                    local_path,
+                    local_is_dir,
+                ) {
+                    self.delete_remote_item(remote_path, item.is_dir)?;
+                }
+
+                None
+            }
+            Some(item) => Some(item.is_dir),
+            None => None,
+        };
+
+        if local_is_dir {
+            if remote_is_dir.is_none() && !self.add_to_plan(PlanAction::Upload, local_path, true) {
+                rclone::sync::mkdir(&self.remote.name, remote_path)
+                    .map_err(|err| SyncError::rclone(remote_path, err))?;
+                self.changed.set(true);
+            }
+
+            // A dry run doesn't create the directory, so it can't be listed. It'd be
+            // empty anyway.
+            if !(self.planning() && remote_is_dir.is_none() && pass == Pass::Remote) {
+                self.recurse(pass, local_path, remote_path);
+            }
+        } else if !self.add_to_plan(PlanAction::Upload, local_path, false) {
+            // Keep the remote file that's about to be replaced.
+            if remote_is_dir == Some(false) {
+                self.versions.keep_remote(local_path, remote_path)?;
+            }
+
+            let size = fs::metadata(local_path)
+                .map_err(|err| SyncError::local(local_path, &err))?
+                .len();
+            self.queue_transfer(Transfer {
+                kind: TransferKind::Upload,
+                local_path: local_path.to_owned(),
+                remote_path: remote_path.to_owned(),
+                size,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Pull an item from the remote to the local machine, replacing whatever is
+    /// currently there.
+    fn pull_remote_to_local(
+        &self,
+        pass: Pass,
+        local_path: &str,
+        remote_path: &str,
+        local_is_dir: Option<bool>,
+        remote_item: &RcloneRemoteItem,
+    ) -> Result<(), SyncError> {
+        // Make sure the file types match up.
+        let local_is_dir = match local_is_dir {
+            Some(is_dir) if is_dir != remote_item.is_dir => {
+                if !self.add_to_plan(
+                    PlanAction::TypeChange(SyncRoot::Local),
+                    local_path,
+                    remote_item.is_dir,
+                ) {
+                    self.delete_local_item(local_path)?;
+                }
+
+                None
+            }
+            local_is_dir => local_is_dir,
+        };
+
+        if remote_item.is_dir {
+            if local_is_dir.is_none() && !self.add_to_plan(PlanAction::Download, local_path, true) {
+                fs::create_dir(local_path).map_err(|err| SyncError::local(local_path, &err))?;
+                self.changed.set(true);
+            }
+
+            // A dry run doesn't create the directory, so it can't be listed. It'd be
+            // empty anyway.
+            if !(self.planning() && local_is_dir.is_none() && pass == Pass::Local) {
+                self.recurse(pass, local_path, remote_path);
+            }
+        } else if !self.add_to_plan(PlanAction::Download, local_path, false) {
+            // Keep the local file that's about to be replaced.
+            if local_is_dir == Some(false) {
+                self.versions.keep_local(local_path)?;
+            }
+
+            self.queue_transfer(Transfer {
+                kind: TransferKind::Download,
+                local_path: local_path.to_owned(),
+                remote_path: remote_path.to_owned(),
+                size: remote_item.size.max(0) as u64,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Get the UNIX timestamp of a local item's last modification.
+fn get_timestamp(metadata: &Metadata) -> u64 {
+    metadata
+        .modified()
+        .unwrap()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Get a free path to move a conflicting local item to, in the form of
+/// `report (conflicted copy 2026-10-18 host).odt`.
+pub fn conflicted_copy_path(local_path: &str) -> String {
+    let path = Path::new(local_path);
+    let stem = path.file_stem().unwrap().to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    // The date the user sees on their clock, not the one in UTC.
+    let date = util::now_local().format("%Y-%m-%d").unwrap();
+    let host = unistd::gethostname()
+        .ok()
+        .and_then(|host| host.into_string().ok())
+        .unwrap_or_else(|| "unknown".to_owned());
+
+    let mut copy_number = 1;
+
+    loop {
+        let label = if copy_number == 1 {
+            tr::tr!("conflicted copy {} {}", date, host)
+        } else {
+            tr::tr!("conflicted copy {} {} {}", date, host, copy_number)
+        };
+        let copy_path = path.with_file_name(format!("{stem} ({label}){extension}"));
+
+        if !copy_path.exists() {
+            return copy_path.to_str().unwrap().to_owned();
+        }
+
+        copy_number += 1;
+    }
+}
+
+/// Get the remote subfolders left out of syncing in the sync directory with
+/// the ID of `sync_dir_id`, relative to its roots.
+pub fn skipped_dirs(db: &DatabaseConnection, sync_dir_id: i32) -> Vec<String> {
+    util::await_future(
+        SyncSkippedDirsEntity::find()
+            .filter(SyncSkippedDirsColumn::SyncDirId.eq(sync_dir_id))
+            .all(db),
+    )
+    .unwrap()
+    .into_iter()
+    .map(|dir| dir.path)
+    .collect()
+}
+
+/// Set the remote subfolders to leave out of syncing in a sync directory,
+/// relative to its roots.
+///
+/// Anything inside of them is neither downloaded nor deleted on the remote
+/// from then on. What was recorded about their items at previous syncs gets
+/// forgotten, so that any local copy is left alone, and both sides get
+/// compared from scratch if they're synced again.
+pub fn set_skipped_dirs(db: &DatabaseConnection, sync_dir: &SyncDirsModel, dirs: &[String]) {
+    util::await_future(async {
+        SyncSkippedDirsEntity::delete_many()
+            .filter(SyncSkippedDirsColumn::SyncDirId.eq(sync_dir.id))
+            .exec(db)
+            .await
+            .unwrap();
+
+        for dir in dirs {
+            SyncSkippedDirsActiveModel {
+                sync_dir_id: ActiveValue::Set(sync_dir.id),
+                path: ActiveValue::Set(dir.clone()),
+                ..Default::default()
+            }
+            .insert(db)
+            .await
+            .unwrap();
+        }
+
+        let root = format!("{}/", sync_dir.local_path);
+        let sync_items = SyncItemsEntity::find()
+            .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
+            .all(db)
+            .await
+            .unwrap();
+
+        for sync_item in sync_items {
+            let Some(relative_path) = sync_item.local_path.strip_prefix(&root) else {
+                continue;
+            };
+
+            if dirs.iter().any(|dir| is_inside(relative_path, dir)) {
+                sync_item.delete(db).await.unwrap();
+            }
+        }
+    });
+}
+
+/// Remove the local copy of a remote subfolder that was left out of syncing,
+/// by moving it to the user's trash. `dir` is relative to the roots of the
+/// sync directory. Nothing on the remote is touched.
+pub fn remove_local_copy(sync_dir: &SyncDirsModel, dir: &str) -> Result<(), SyncError> {
+    let local_path = format!("{}/{dir}", sync_dir.local_path);
+
+    if fs::symlink_metadata(&local_path).is_err() {
+        return Ok(());
+    }
+
+    trash::trash_local(Path::new(&local_path)).map_err(|err| SyncError::local(&local_path, &err))
+}
+
+/// Check if `path` is `dir` or inside of it.
+fn is_inside(path: &str, dir: &str) -> bool {
+    path == dir || path.starts_with(&format!("{dir}/"))
+}
+
+/// Stop syncing a directory pair, removing it and all of its sync items from
+/// the database.
+pub fn delete_sync_dir(db: &DatabaseConnection, local_path: &str, remote_path: &str) {
+    util::await_future(async {
+        let sync_dir = SyncDirsEntity::find()
+            .filter(SyncDirsColumn::LocalPath.eq(local_path.to_owned()))
+            .filter(SyncDirsColumn::RemotePath.eq(remote_path.to_owned()))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+
+        SyncItemsEntity::delete_many()
+            .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
+            .exec(db)
+            .await
+            .unwrap();
+        SyncRetriesEntity::delete_many()
+            .filter(SyncRetriesColumn::SyncDirId.eq(sync_dir.id))
+            .exec(db)
+            .await
+            .unwrap();
+        SyncEventsEntity::delete_many()
+            .filter(SyncEventsColumn::SyncDirId.eq(sync_dir.id))
+            .exec(db)
+            .await
+            .unwrap();
+        SyncSkippedDirsEntity::delete_many()
+            .filter(SyncSkippedDirsColumn::SyncDirId.eq(sync_dir.id))
+            .exec(db)
+            .await
+            .unwrap();
+        sync_dir.delete(db).await.unwrap();
+    });
+}
+
+/// Delete a remote, along with all of its sync directories and its Rclone
+/// config.
+pub fn delete_remote(db: &DatabaseConnection, remote_name: &str) {
+    util::await_future(async {
+        let db_remote = RemotesEntity::find()
+            .filter(RemotesColumn::Name.eq(remote_name.to_owned()))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        let sync_dirs = SyncDirsEntity::find()
+            .filter(SyncDirsColumn::RemoteId.eq(db_remote.id))
+            .all(db)
+            .await
+            .unwrap();
+
+        for sync_dir in sync_dirs {
+            SyncItemsEntity::delete_many()
+                .filter(SyncItemsColumn::SyncDirId.eq(sync_dir.id))
+                .exec(db)
+                .await
+                .unwrap();
+            SyncRetriesEntity::delete_many()
+                .filter(SyncRetriesColumn::SyncDirId.eq(sync_dir.id))
+                .exec(db)
+                .await
+                .unwrap();
+            SyncEventsEntity::delete_many()
+                .filter(SyncEventsColumn::SyncDirId.eq(sync_dir.id))
+                .exec(db)
+                .await
+                .unwrap();
+            SyncSkippedDirsEntity::delete_many()
+                .filter(SyncSkippedDirsColumn::SyncDirId.eq(sync_dir.id))
+                .exec(db)
+                .await
+                .unwrap();
+            sync_dir.delete(db).await.unwrap();
+        }
+
+        db_remote.delete(db).await.unwrap();
+    });
+
+    rclone::sync::delete_config(remote_name).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conflicted_copy_path_keeps_extension() {
+        let dir =
+            std::env::temp_dir().join(format!("celeste-conflict-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let local_path = dir.join("report.txt");
+        let local_path = local_path.to_str().unwrap();
+
+        let copy_path = conflicted_copy_path(local_path);
+        assert!(copy_path.starts_with(&format!("{}/report (", dir.display())));
+        assert!(copy_path.ends_with(").txt"));
+
+        // A copy that already exists doesn't get overwritten.
+        fs::write(&copy_path, "").unwrap();
+        let second_copy_path = conflicted_copy_path(local_path);
+        assert_ne!(second_copy_path, copy_path);
+        assert!(second_copy_path.ends_with(" 2).txt"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn conflicted_copy_path_without_extension() {
+        let copy_path = conflicted_copy_path("/nonexistent/celeste/Makefile");
+        assert!(copy_path.starts_with("/nonexistent/celeste/Makefile ("));
+        assert!(copy_path.ends_with(')'));
+    }
+
+    #[test]
+    fn is_inside_matches_dir_and_children() {
+        assert!(is_inside("docs", "docs"));
+        assert!(is_inside("docs/report.txt", "docs"));
+        assert!(is_inside("docs/old/report.txt", "docs"));
+        assert!(!is_inside("documents", "docs"));
+        assert!(!is_inside("docs2/report.txt", "docs"));
+        assert!(!is_inside("other/docs", "docs"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Previews of what a sync would do.
+//!
+//! Merging two trees that both already hold data can do a lot at once, so a
+//! [`SyncEngine`](super::SyncEngine) can walk through a sync directory without
+//! changing anything, and collect the actions it would take into a
+//! [`SyncPlan`] for the user to review first.
+use super::{ConflictResolution, SyncError, SyncRoot};
+
+/// An action a sync would take on a single item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanAction {
+    /// Copy the local item to the remote.
+    Upload,
+    /// Copy the remote item to the local machine.
+    Download,
+    /// Delete the local item.
+    DeleteLocal,
+    /// Delete the remote item.
+    DeleteRemote,
+    /// Move the remote item to follow the local one being moved or renamed.
+    Move,
+    /// The item changed on both sides. Contains the side the sync directory's
+    /// conflict policy would keep, or [`None`] if the user gets asked.
+    Conflict(Option<ConflictResolution>),
+    /// A file is replaced by a directory or the other way around. Contains
+    /// the side the item gets replaced on, which is also listed as an upload
+    /// or download.
+    TypeChange(SyncRoot),
+}
+
+/// A single item in a [`SyncPlan`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlannedItem {
+    pub action: PlanAction,
+    /// The path of the item, relative to the roots of the sync directory.
+    pub path: String,
+    pub is_dir: bool,
+}
+
+/// Everything a sync would currently do, as found by
+/// [`SyncEngine::plan`](super::SyncEngine::plan).
+#[derive(Clone, Debug, Default)]
+pub struct SyncPlan {
+    pub items: Vec<PlannedItem>,
+    /// The errors found while checking, for items whose action couldn't be
+    /// worked out.
+    pub errors: Vec<SyncError>,
+}
+
+impl SyncPlan {
+    /// Get the number of items with the given action.
+    pub fn count(&self, action: PlanAction) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.action == action)
+            .count()
+    }
+
+    /// Whether a sync wouldn't do anything at all.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.errors.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(action: PlanAction, path: &str) -> PlannedItem {
+        PlannedItem {
+            action,
+            path: path.to_owned(),
+            is_dir: false,
+        }
+    }
+
+    #[test]
+    fn default_plan_is_empty() {
+        let plan = SyncPlan::default();
+        assert!(plan.is_empty());
+        assert_eq!(plan.count(PlanAction::Upload), 0);
+    }
+
+    #[test]
+    fn count_by_action() {
+        let plan = SyncPlan {
+            items: vec![
+                item(PlanAction::Upload, "a.txt"),
+                item(PlanAction::Upload, "b.txt"),
+                item(PlanAction::Download, "c.txt"),
+                item(PlanAction::DeleteRemote, "d.txt"),
+                item(PlanAction::Conflict(None), "e.txt"),
+                item(
+                    PlanAction::Conflict(Some(ConflictResolution::Local)),
+                    "f.txt",
+                ),
+                item(PlanAction::TypeChange(SyncRoot::Remote), "g"),
+            ],
+            errors: vec![],
+        };
+
+        assert!(!plan.is_empty());
+        assert_eq!(plan.count(PlanAction::Upload), 2);
+        assert_eq!(plan.count(PlanAction::Download), 1);
+        assert_eq!(plan.count(PlanAction::DeleteLocal), 0);
+        assert_eq!(plan.count(PlanAction::DeleteRemote), 1);
+        assert_eq!(plan.count(PlanAction::Move), 0);
+        // Conflicts are told apart by how they'd get resolved.
+        assert_eq!(plan.count(PlanAction::Conflict(None)), 1);
+        assert_eq!(
+            plan.count(PlanAction::Conflict(Some(ConflictResolution::Local))),
+            1
+        );
+        assert_eq!(
+            plan.count(PlanAction::Conflict(Some(ConflictResolution::Remote))),
+            0
+        );
+        assert_eq!(plan.count(PlanAction::TypeChange(SyncRoot::Remote)), 1);
+    }
+
+    #[test]
+    fn plan_with_only_errors_is_not_empty() {
+        let plan = SyncPlan {
+            items: vec![],
+            errors: vec![SyncError::BothMoreCurrent(
+                "/home/user/a.txt".to_owned(),
+                "a.txt".to_owned(),
+            )],
+        };
+        assert!(!plan.is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+//! Scheduling when to check a remote for changes.
+//!
+//! Checking a remote means listing every sync directory on it, which eats into
+//! the API quotas of services like Google Drive and Dropbox. A
+//! [`PollScheduler`] spaces those checks out, backing off further each time a
+//! check finds nothing to do.
+use std::time::{Duration, Instant};
+
+/// The highest power of two the base interval gets multiplied by while backing
+/// off.
+const MAX_BACKOFF_EXPONENT: u32 = 4;
+
+/// Decides when a remote is due for its next check.
+pub struct PollScheduler {
+    /// The interval between checks when changes were just found.
+    base_interval: Duration,
+    /// The number of checks in a row that found nothing to do, up to
+    /// [`MAX_BACKOFF_EXPONENT`].
+    backoff: u32,
+    last_check: Option<Instant>,
+}
+
+impl PollScheduler {
+    /// Create a scheduler that's due right away.
+    pub fn new(base_interval: Duration) -> Self {
+        Self {
+            base_interval,
+            backoff: 0,
+            last_check: None,
+        }
+    }
+
+    /// Change the interval between checks, i.e. after the user changed it.
+    pub fn set_base_interval(&mut self, base_interval: Duration) {
+        self.base_interval = base_interval;
+    }
+
+    /// Get the current interval between checks, including any backoff.
+    pub fn interval(&self) -> Duration {
+        self.base_interval * 2u32.pow(self.backoff)
+    }
+
+    /// Whether the remote should be checked now.
+    pub fn is_due(&self) -> bool {
+        self.last_check
+            .map_or(true, |last_check| last_check.elapsed() >= self.interval())
+    }
+
+    /// Make the remote due for a check right away, without touching the
+    /// backoff.
+    pub fn force(&mut self) {
+        self.last_check = None;
+    }
+
+    /// Record that the remote was just checked. Finding changes resets the
+    /// interval to the base one, while finding none doubles it.
+    pub fn checked(&mut self, changes_found: bool) {
+        self.last_check = Some(Instant::now());
+
+        if changes_found {
+            self.backoff = 0;
+        } else {
+            self.backoff = (self.backoff + 1).min(MAX_BACKOFF_EXPONENT);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn new_scheduler_is_due() {
+        let poller = PollScheduler::new(BASE);
+        assert!(poller.is_due());
+        assert_eq!(poller.interval(), BASE);
+    }
+
+    #[test]
+    fn backs_off_without_changes() {
+        let mut poller = PollScheduler::new(BASE);
+
+        poller.checked(false);
+        assert!(!poller.is_due());
+        assert_eq!(poller.interval(), BASE * 2);
+
+        poller.checked(false);
+        assert_eq!(poller.interval(), BASE * 4);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let mut poller = PollScheduler::new(BASE);
+
+        for _ in 0..MAX_BACKOFF_EXPONENT + 3 {
+            poller.checked(false);
+        }
+
+        assert_eq!(poller.interval(), BASE * 2u32.pow(MAX_BACKOFF_EXPONENT));
+    }
+
+    #[test]
+    fn changes_reset_backoff() {
+        let mut poller = PollScheduler::new(BASE);
+        poller.checked(false);
+        poller.checked(false);
+
+        poller.checked(true);
+        assert_eq!(poller.interval(), BASE);
+    }
+
+    #[test]
+    fn force_keeps_backoff() {
+        let mut poller = PollScheduler::new(BASE);
+        poller.checked(false);
+
+        poller.force();
+        assert!(poller.is_due());
+        assert_eq!(poller.interval(), BASE * 2);
+    }
+
+    #[test]
+    fn base_interval_can_change() {
+        let mut poller = PollScheduler::new(BASE);
+        poller.checked(false);
+
+        poller.set_base_interval(BASE * 3);
+        assert_eq!(poller.interval(), BASE * 6);
+    }
+}
@@ -0,0 +1,141 @@
+//! Retrying items that failed to sync.
+//!
+//! Plenty of errors go away on their own, such as a request timing out or a
+//! file still being written to. Instead of reporting those right away, a
+//! failed item gets tried again on later syncs with an increasing delay, and
+//! only gets reported once it keeps failing.
+//!
+//! Items whose transfer the user cancelled are held in the same way, until
+//! they change again.
+use crate::{
+    entities::{SyncRetriesActiveModel, SyncRetriesColumn, SyncRetriesEntity},
+    util,
+};
+use sea_orm::{entity::prelude::*, ActiveValue, DatabaseConnection};
+use time::OffsetDateTime;
+
+/// The number of times in a row an item can fail before its error gets
+/// reported.
+const MAX_ATTEMPTS: i32 = 5;
+
+/// The number of seconds to wait before trying an item again for the first
+/// time. This doubles with each failed attempt.
+const BASE_DELAY: i64 = 30;
+
+/// The retry bookkeeping for the items in a sync directory.
+pub struct Retries {
+    db: DatabaseConnection,
+    sync_dir_id: i32,
+}
+
+impl Retries {
+    pub fn new(db: DatabaseConnection, sync_dir_id: i32) -> Self {
+        Self { db, sync_dir_id }
+    }
+
+    /// Whether the item at `path` failed before, and shouldn't be tried again
+    /// yet.
+    pub fn is_waiting(&self, path: &str) -> bool {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+
+        util::await_future(
+            SyncRetriesEntity::find()
+                .filter(SyncRetriesColumn::SyncDirId.eq(self.sync_dir_id))
+                .filter(SyncRetriesColumn::Path.eq(path.to_owned()))
+                .filter(SyncRetriesColumn::NextRetry.gt(now))
+                .one(&self.db),
+        )
+        .unwrap()
+        .is_some()
+    }
+
+    /// Record a failed attempt at syncing the item at `path`, scheduling the
+    /// next one. Returns `true` if the item failed too many times in a row, in
+    /// which case the error should be reported instead, and the bookkeeping
+    /// starts over.
+    pub fn failed(&self, path: &str, error: &str) -> bool {
+        let retry = util::await_future(
+            SyncRetriesEntity::find()
+                .filter(SyncRetriesColumn::SyncDirId.eq(self.sync_dir_id))
+                .filter(SyncRetriesColumn::Path.eq(path.to_owned()))
+                .one(&self.db),
+        )
+        .unwrap();
+        let attempts = retry.as_ref().map_or(0, |retry| retry.attempts) + 1;
+
+        if attempts >= MAX_ATTEMPTS {
+            self.clear(&[path]);
+            return true;
+        }
+
+        let mut active_model: SyncRetriesActiveModel = match retry {
+            Some(retry) => retry.into(),
+            None => SyncRetriesActiveModel {
+                sync_dir_id: ActiveValue::Set(self.sync_dir_id),
+                path: ActiveValue::Set(path.to_owned()),
+                ..Default::default()
+            },
+        };
+        let delay = BASE_DELAY << (attempts - 1);
+        active_model.attempts = ActiveValue::Set(attempts);
+        active_model.next_retry =
+            ActiveValue::Set(OffsetDateTime::now_utc().unix_timestamp() + delay);
+        active_model.last_error = ActiveValue::Set(error.to_owned());
+        util::await_future(active_model.save(&self.db)).unwrap();
+
+        false
+    }
+
+    /// Leave the item at `path` alone until its timestamp changes from
+    /// `timestamp`, i.e. because the user cancelled its transfer.
+    pub fn hold(&self, path: &str, timestamp: i64) {
+        self.clear(&[path]);
+        util::await_future(
+            SyncRetriesActiveModel {
+                sync_dir_id: ActiveValue::Set(self.sync_dir_id),
+                path: ActiveValue::Set(path.to_owned()),
+                attempts: ActiveValue::Set(0),
+                next_retry: ActiveValue::Set(0),
+                last_error: ActiveValue::Set(String::new()),
+                held_timestamp: ActiveValue::Set(Some(timestamp)),
+                ..Default::default()
+            }
+            .insert(&self.db),
+        )
+        .unwrap();
+    }
+
+    /// Whether the item at `path` is being held with its current timestamp of
+    /// `timestamp`. A hold is released once the item's timestamp changes.
+    pub fn is_held(&self, path: &str, timestamp: Option<i64>) -> bool {
+        let Some(retry) = util::await_future(
+            SyncRetriesEntity::find()
+                .filter(SyncRetriesColumn::SyncDirId.eq(self.sync_dir_id))
+                .filter(SyncRetriesColumn::Path.eq(path.to_owned()))
+                .filter(SyncRetriesColumn::HeldTimestamp.is_not_null())
+                .one(&self.db),
+        )
+        .unwrap() else {
+            return false;
+        };
+
+        if retry.held_timestamp == timestamp {
+            return true;
+        }
+
+        util::await_future(retry.delete(&self.db)).unwrap();
+        false
+    }
+
+    /// Forget about any failed attempts for the items at `paths`, i.e. because
+    /// they synced successfully.
+    pub fn clear(&self, paths: &[&str]) {
+        util::await_future(
+            SyncRetriesEntity::delete_many()
+                .filter(SyncRetriesColumn::SyncDirId.eq(self.sync_dir_id))
+                .filter(SyncRetriesColumn::Path.is_in(paths.iter().map(|path| path.to_string())))
+                .exec(&self.db),
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,303 @@
+//! Running file transfers in parallel.
+//!
+//! Copying one file at a time makes syncing lots of small files painfully
+//! slow, as most of that time is spent waiting on the remote. A
+//! [`TransferQueue`] runs up to a set number of copies at once on their own
+//! threads, while the sync engine keeps walking through the directory.
+//!
+//! Each copy runs as an Rclone job, which gets polled for its progress until it
+//! finishes.
+use crate::rclone::{self, RcloneError, RcloneStats};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// How often to check on a running transfer.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The way a [`Transfer`] copies its file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferKind {
+    /// Copy the local file to the remote.
+    Upload,
+    /// Copy the remote file to the local machine.
+    Download,
+}
+
+/// A single file copy between the local machine and the remote.
+#[derive(Clone, Debug)]
+pub struct Transfer {
+    pub kind: TransferKind,
+    pub local_path: String,
+    pub remote_path: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+}
+
+/// How far along a running [`Transfer`] is.
+#[derive(Clone, Debug)]
+pub struct FileProgress {
+    /// The ID of the Rclone job running the transfer, which can be passed to
+    /// [`rclone::sync::stop_job`] to cancel it.
+    pub job_id: u64,
+    pub kind: TransferKind,
+    pub local_path: String,
+    /// The number of bytes transferred so far.
+    pub bytes: u64,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The current transfer speed in bytes per second.
+    pub speed: f64,
+    /// The estimated number of seconds left, if known.
+    pub eta: Option<u64>,
+}
+
+/// How far along the transfers started by a [`TransferQueue`] are.
+#[derive(Clone, Debug, Default)]
+pub struct TransferProgress {
+    /// The number of transfers that finished, whether they succeeded or not.
+    pub finished: usize,
+    /// The number of transfers started in total.
+    pub total: usize,
+    /// The number of bytes transferred so far.
+    pub bytes: u64,
+    /// The number of bytes to transfer in total.
+    pub total_bytes: u64,
+    /// The combined speed of the running transfers in bytes per second.
+    pub speed: f64,
+    /// The progress of each running transfer.
+    pub files: Vec<FileProgress>,
+}
+
+/// The Rclone jobs started by a [`TransferQueue`] that are still running.
+#[derive(Default)]
+struct RunningJobs {
+    ids: HashSet<u64>,
+    /// Whether [`TransferQueue::cancel_all`] was called, in which case jobs
+    /// that start afterwards get stopped right away.
+    cancelled: bool,
+}
+
+/// A finished transfer, along with its result.
+pub type FinishedTransfer = (Transfer, Result<(), RcloneError>);
+
+/// Runs up to a set number of [`Transfer`]s at once.
+pub struct TransferQueue {
+    remote_name: String,
+    max_transfers: usize,
+    /// The transfers that are currently running.
+    running: Vec<Transfer>,
+    sender: Sender<FinishedTransfer>,
+    receiver: Arc<Mutex<Receiver<FinishedTransfer>>>,
+    /// The number of transfers started so far.
+    started: usize,
+    /// The number of transfers that finished so far, whether they succeeded or
+    /// not.
+    finished: usize,
+    /// The number of bytes of the transfers started so far.
+    started_bytes: u64,
+    /// The number of bytes of the transfers that finished so far.
+    finished_bytes: u64,
+    /// The progress of the running transfers, as updated by the threads
+    /// running them, keyed by their local path.
+    file_progress: Arc<Mutex<HashMap<String, FileProgress>>>,
+    /// The jobs running the transfers. These are tracked apart from
+    /// `file_progress`, as a job is running before its progress is first
+    /// recorded.
+    jobs: Arc<Mutex<RunningJobs>>,
+}
+
+impl TransferQueue {
+    /// Create a queue running at most `max_transfers` transfers on
+    /// `remote_name` at once.
+    pub fn new(remote_name: &str, max_transfers: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Self {
+            remote_name: remote_name.to_owned(),
+            max_transfers: max_transfers.max(1),
+            running: vec![],
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            started: 0,
+            finished: 0,
+            started_bytes: 0,
+            finished_bytes: 0,
+            file_progress: Arc::new(Mutex::new(HashMap::new())),
+            jobs: Arc::new(Mutex::new(RunningJobs::default())),
+        }
+    }
+
+    /// Whether no transfers are running.
+    pub fn is_empty(&self) -> bool {
+        self.running.is_empty()
+    }
+
+    /// Whether the maximum number of transfers are running, meaning one has to
+    /// finish before another can be started.
+    pub fn is_full(&self) -> bool {
+        self.running.len() >= self.max_transfers
+    }
+
+    /// Whether a transfer is running for `local_path`, or for anything below
+    /// it.
+    pub fn is_running_at(&self, local_path: &str) -> bool {
+        let dir_prefix = format!("{local_path}/");
+
+        self.running.iter().any(|transfer| {
+            transfer.local_path == local_path || transfer.local_path.starts_with(&dir_prefix)
+        })
+    }
+
+    /// Get how far along the transfers started so far are.
+    pub fn progress(&self) -> TransferProgress {
+        let file_progress = self.file_progress.lock().unwrap();
+        let mut files: Vec<FileProgress> = file_progress.values().cloned().collect();
+        files.sort_by(|a, b| a.local_path.cmp(&b.local_path));
+
+        TransferProgress {
+            finished: self.finished,
+            total: self.started,
+            bytes: self.finished_bytes + files.iter().map(|file| file.bytes).sum::<u64>(),
+            total_bytes: self.started_bytes,
+            speed: files.iter().map(|file| file.speed).sum(),
+            files,
+        }
+    }
+
+    /// Start a transfer in the background. This doesn't check
+    /// [`Self::is_full`], so callers need to do so first.
+    pub fn start(&mut self, transfer: Transfer) {
+        let sender = self.sender.clone();
+        let remote_name = self.remote_name.clone();
+        let file_progress = self.file_progress.clone();
+        let jobs = self.jobs.clone();
+        self.running.push(transfer.clone());
+        self.started += 1;
+        self.started_bytes += transfer.size;
+
+        thread::spawn(move || {
+            let job = match transfer.kind {
+                TransferKind::Upload => rclone::sync::copy_to_remote(
+                    &transfer.local_path,
+                    &remote_name,
+                    &transfer.remote_path,
+                ),
+                TransferKind::Download => rclone::sync::copy_to_local(
+                    &transfer.local_path,
+                    &remote_name,
+                    &transfer.remote_path,
+                ),
+            };
+            let result = job.and_then(|job_id| {
+                {
+                    let mut jobs = jobs.lock().unwrap();
+                    jobs.ids.insert(job_id);
+
+                    // The queue got cancelled while the job was being started.
+                    if jobs.cancelled {
+                        let _ = rclone::sync::stop_job(job_id);
+                    }
+                }
+
+                let result = wait_for_job(job_id, &transfer, &file_progress);
+                jobs.lock().unwrap().ids.remove(&job_id);
+                result
+            });
+            file_progress.lock().unwrap().remove(&transfer.local_path);
+
+            // The queue only goes away once everything it started has finished, so
+            // this can't fail.
+            let _ = sender.send((transfer, result));
+        });
+    }
+
+    /// Get the receiver that finished transfers get sent to. Each one that gets
+    /// received has to be passed to [`Self::finish`].
+    ///
+    /// This is handed out on its own so that waiting on it doesn't need the
+    /// queue to be borrowed.
+    pub fn receiver(&self) -> Arc<Mutex<Receiver<FinishedTransfer>>> {
+        self.receiver.clone()
+    }
+
+    /// Cancel all running transfers, including ones that are still being
+    /// started. They still have to be received from [`Self::receiver`] as
+    /// usual, and fail with an error of the
+    /// [`RcloneErrorKind::Cancelled`](rclone::RcloneErrorKind::Cancelled) kind.
+    pub fn cancel_all(&self) {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.cancelled = true;
+
+        for job_id in &jobs.ids {
+            // The job may have finished in the meantime, in which case there's nothing
+            // left to cancel.
+            let _ = rclone::sync::stop_job(*job_id);
+        }
+    }
+
+    /// Mark a transfer received from [`Self::receiver`] as finished.
+    pub fn finish(&mut self, transfer: &Transfer) {
+        if let Some(index) = self.running.iter().position(|running| {
+            running.local_path == transfer.local_path && running.remote_path == transfer.remote_path
+        }) {
+            self.running.remove(index);
+            self.finished += 1;
+            self.finished_bytes += transfer.size;
+        }
+    }
+}
+
+/// Wait for the Rclone job running `transfer` to finish, keeping its entry in
+/// `file_progress` up to date in the meantime.
+fn wait_for_job(
+    job_id: u64,
+    transfer: &Transfer,
+    file_progress: &Mutex<HashMap<String, FileProgress>>,
+) -> Result<(), RcloneError> {
+    let update_progress = |stats: RcloneStats| {
+        file_progress.lock().unwrap().insert(
+            transfer.local_path.clone(),
+            FileProgress {
+                job_id,
+                kind: transfer.kind,
+                local_path: transfer.local_path.clone(),
+                bytes: stats.bytes,
+                size: transfer.size.max(stats.total_bytes),
+                speed: stats.speed,
+                eta: stats.eta,
+            },
+        );
+    };
+
+    // Make the transfer show up right away, so that it can be cancelled before
+    // its progress is known.
+    update_progress(RcloneStats::default());
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let status = rclone::sync::job_status(job_id)?;
+
+        if status.finished {
+            return if status.success {
+                Ok(())
+            } else {
+                Err(RcloneError {
+                    error: status.error,
+                })
+            };
+        }
+
+        // The progress is only for showing to the user, so it isn't worth failing the
+        // transfer over.
+        if let Ok(stats) = rclone::sync::job_stats(job_id) {
+            update_progress(stats);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Moving deleted items to a trash instead of removing them for good.
+//!
+//! Local items go to the user's trash as described by the [FreeDesktop trash
+//! specification](https://specifications.freedesktop.org/trash-spec/trashspec-latest.html),
+//! so they can be restored from any file manager. Items on another filesystem
+//! than the user's home trash go to a `.Trash-$uid` directory at the top of
+//! their own filesystem, so they don't have to be copied. Remote items get
+//! moved into a dated directory inside the sync directory's remote trash path.
+use crate::{
+    rclone::{self, RcloneError},
+    util,
+};
+use nix::unistd;
+use std::{
+    fs::{self, DirBuilder},
+    io,
+    os::unix::{
+        ffi::OsStrExt,
+        fs::{self as unix_fs, DirBuilderExt, MetadataExt},
+    },
+    path::{Path, PathBuf},
+};
+
+/// Move a local file or directory to the user's trash.
+pub fn trash_local(path: &Path) -> io::Result<()> {
+    let (trash_dir, info_path_value) = match topdir_trash(path) {
+        Some((trash_dir, topdir)) => (
+            trash_dir,
+            encode_path(path.strip_prefix(&topdir).unwrap_or(path)),
+        ),
+        None => (util::get_trash_dir(), encode_path(path)),
+    };
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    fs::create_dir_all(&files_dir)?;
+    fs::create_dir_all(&info_dir)?;
+
+    // Find a name that isn't in use in the trash yet.
+    let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+    let mut trash_name = file_name.clone();
+    let mut copy_number = 1;
+
+    while files_dir.join(&trash_name).exists() || info_dir.join(info_name(&trash_name)).exists() {
+        copy_number += 1;
+        trash_name = format!("{file_name}.{copy_number}");
+    }
+
+    // The info file has to be written first, so that a trashed item never exists
+    // without one. The specification wants its deletion date in local time.
+    let deletion_date = util::now_local().format("%Y-%m-%dT%H:%M:%S").unwrap();
+    let info = format!("[Trash Info]\nPath={info_path_value}\nDeletionDate={deletion_date}\n");
+    let info_path = info_dir.join(info_name(&trash_name));
+    fs::write(&info_path, info)?;
+
+    let trash_path = files_dir.join(&trash_name);
+    let result = match fs::rename(path, &trash_path) {
+        // The item is on another filesystem than the trash, so we have to copy it over
+        // instead.
+        Err(err) if err.raw_os_error() == Some(nix::libc::EXDEV) => {
+            copy_recursive(path, &trash_path).and_then(|_| {
+                if fs::symlink_metadata(path)?.is_dir() {
+                    fs::remove_dir_all(path)
+                } else {
+                    fs::remove_file(path)
+                }
+            })
+        }
+        result => result,
+    };
+
+    if result.is_err() {
+        let _ = fs::remove_file(&info_path);
+    }
+
+    result
+}
+
+/// Get the trash directory at the top of the filesystem `path` is on, along
+/// with the top directory itself, if `path` isn't on the same filesystem as
+/// the user's home trash. Returns [`None`] if the home trash should be used
+/// instead, including when the filesystem's own trash can't be set up.
+fn topdir_trash(path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let device = fs::symlink_metadata(path).ok()?.dev();
+
+    // The home trash might not exist yet, so go by the closest directory above it
+    // that does.
+    let home_device = util::get_trash_dir()
+        .ancestors()
+        .find_map(|dir| fs::metadata(dir).ok())?
+        .dev();
+
+    if device == home_device {
+        return None;
+    }
+
+    // The top directory is the highest directory that's still on the same device.
+    let mut topdir = path.parent()?.to_owned();
+    while let Some(parent) = topdir.parent()
+        && fs::metadata(parent).is_ok_and(|metadata| metadata.dev() == device)
+    {
+        topdir = parent.to_owned();
+    }
+
+    let trash_dir = topdir.join(topdir_trash_name());
+    DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&trash_dir)
+        .ok()?;
+
+    Some((trash_dir, topdir))
+}
+
+/// Get the name of the trash directory at the top of a filesystem, which is
+/// specific to the current user.
+pub fn topdir_trash_name() -> String {
+    format!(".Trash-{}", unistd::getuid())
+}
+
+/// Move a file or directory on the remote into `trash_path`, under a
+/// directory for today's date.
+pub fn trash_remote(
+    remote_name: &str,
+    trash_path: &str,
+    remote_path: &str,
+    is_dir: bool,
+) -> Result<(), RcloneError> {
+    // Use the same date as the local trash, so an item deleted on both sides ends
+    // up under the same day.
+    let date = util::now_local().format("%Y-%m-%d").unwrap();
+    let base_path = format!("{}/{date}/{}", util::strip_slashes(trash_path), remote_path);
+
+    // Don't overwrite anything that was already trashed at the same path today. A
+    // file's number goes before its extension, so that it still opens the same.
+    let (stem, extension) = match base_path.rsplit_once('.') {
+        Some((stem, extension)) if !is_dir && !stem.ends_with('/') && !extension.contains('/') => {
+            (stem, format!(".{extension}"))
+        }
+        _ => (base_path.as_str(), String::new()),
+    };
+    let mut dst_path = base_path.clone();
+    let mut copy_number = 1;
+
+    while rclone::sync::stat(remote_name, &dst_path)?.is_some() {
+        copy_number += 1;
+        dst_path = format!("{stem}.{copy_number}{extension}");
+    }
+
+    if is_dir {
+        rclone::sync::move_dir(remote_name, remote_path, &dst_path)
+    } else {
+        rclone::sync::move_file(remote_name, remote_path, &dst_path)
+    }
+}
+
+/// Get the name of the info file for an item in the trash.
+fn info_name(trash_name: &str) -> String {
+    format!("{trash_name}.trashinfo")
+}
+
+/// Percent-encode a path for use in a `.trashinfo` file.
+fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+
+    for byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(*byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    encoded
+}
+
+/// Copy a file or directory and everything inside of it. Symlinks are copied
+/// as symlinks.
+fn copy_recursive(src: &Path, dst: &Path) -> io::Result<()> {
+    let file_type = fs::symlink_metadata(src)?.file_type();
+
+    if file_type.is_symlink() {
+        return unix_fs::symlink(fs::read_link(src)?, dst);
+    } else if !file_type.is_dir() {
+        return fs::copy(src, dst).map(|_| ());
+    }
+
+    fs::create_dir(dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        copy_recursive(&entry.path(), &dst.join(entry.file_name()))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,376 @@
+//! Keeping the previous versions of overwritten files.
+//!
+//! Before a sync overwrites a file on either side, the content being replaced
+//! gets copied into a [`VERSIONS_DIR_NAME`] directory at the root of the local
+//! or remote directory, depending on the sync directory's settings. Each
+//! version is stored at the file's path relative to the root, with the UNIX
+//! timestamp it was kept at appended, i.e. `docs/report.txt.1760791331`. When
+//! a file gets several versions kept in the same second, the later ones get a
+//! number appended as well, i.e. `docs/report.txt.1760791331-2`.
+use super::SyncError;
+use crate::{
+    entities::{SyncDirsModel, VersionStorage},
+    rclone::{self, RcloneErrorKind, RcloneListFilter},
+};
+use std::{collections::HashMap, fs, io, path::Path, sync::Mutex};
+use time::OffsetDateTime;
+
+/// The name of the directory previous versions are kept in.
+pub static VERSIONS_DIR_NAME: &str = ".celeste-versions";
+
+/// The number of seconds in a day.
+const DAY: i64 = 24 * 60 * 60;
+
+lazy_static::lazy_static! {
+    /// The UNIX timestamp each sync directory's versions were last pruned at,
+    /// by the sync directory's ID.
+    static ref LAST_PRUNED: Mutex<HashMap<i32, i64>> = Mutex::new(HashMap::new());
+}
+
+/// A previous version of a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileVersion {
+    /// The UNIX timestamp the version was kept at.
+    pub timestamp: i64,
+    /// The size of the version in bytes.
+    pub size: u64,
+    /// Where the version is kept.
+    pub storage: VersionStorage,
+    /// The path the version is stored at, as a local path or a path on the
+    /// remote, depending on `storage`.
+    path: String,
+}
+
+/// The previous versions kept for a sync directory.
+pub struct Versions {
+    remote_name: String,
+    sync_dir: SyncDirsModel,
+}
+
+impl Versions {
+    pub fn new(remote_name: &str, sync_dir: SyncDirsModel) -> Self {
+        Self {
+            remote_name: remote_name.to_owned(),
+            sync_dir,
+        }
+    }
+
+    /// Check if a path on the remote is inside the versions directory. As the
+    /// local versions directory maps to the same path, this works for
+    /// the paths a local item would get synced to as well.
+    pub fn contains_remote(&self, remote_path: &str) -> bool {
+        let root = self.remote_root();
+        remote_path == root || remote_path.starts_with(&format!("{root}/"))
+    }
+
+    /// Check if a local path is inside the versions directory.
+    pub fn contains_local(&self, local_path: &Path) -> bool {
+        local_path.starts_with(self.local_root())
+    }
+
+    /// Keep the current content of the local file at `local_path`, before it
+    /// gets overwritten.
+    pub fn keep_local(&self, local_path: &str) -> Result<(), SyncError> {
+        if self.sync_dir.version_storage == VersionStorage::Off {
+            return Ok(());
+        }
+        let path = self.version_path(local_path, now())?;
+
+        match self.sync_dir.version_storage {
+            VersionStorage::Off => (),
+            VersionStorage::Local => {
+                fs::create_dir_all(Path::new(&path).parent().unwrap())
+                    .and_then(|_| fs::copy(local_path, &path))
+                    .map_err(|err| SyncError::local(local_path, &err))?;
+            }
+            VersionStorage::Remote => {
+                rclone::sync::upload_file(local_path, &self.remote_name, &path)
+                    .map_err(|err| SyncError::rclone(local_path, err))?;
+            }
+        }
+
+        self.prune_file(local_path)
+    }
+
+    /// Keep the current content of the remote file at `remote_path`, before
+    /// it gets overwritten. `local_path` is the local path it's synced with.
+    pub fn keep_remote(&self, local_path: &str, remote_path: &str) -> Result<(), SyncError> {
+        if self.sync_dir.version_storage == VersionStorage::Off {
+            return Ok(());
+        }
+        let path = self.version_path(local_path, now())?;
+
+        match self.sync_dir.version_storage {
+            VersionStorage::Off => (),
+            VersionStorage::Local => {
+                rclone::sync::download_file(&path, &self.remote_name, remote_path)
+                    .map_err(|err| SyncError::rclone(remote_path, err))?;
+            }
+            VersionStorage::Remote => {
+                rclone::sync::copy_file(&self.remote_name, remote_path, &path)
+                    .map_err(|err| SyncError::rclone(remote_path, err))?;
+            }
+        }
+
+        self.prune_file(local_path)
+    }
+
+    /// Get the versions kept for the file at `local_path` in the sync
+    /// directory's current storage, newest first.
+    pub fn list(&self, local_path: &str) -> Result<Vec<FileVersion>, SyncError> {
+        let storage = self.sync_dir.version_storage;
+        let base_path = self.base_path(local_path);
+        let (parent, file_name) = base_path.rsplit_once('/').unwrap();
+        let prefix = format!("{file_name}.");
+
+        // Find the files in the version's directory with the file's name and a
+        // timestamp.
+        let files: Vec<(String, u64)> = match storage {
+            VersionStorage::Off => vec![],
+            VersionStorage::Local => match fs::read_dir(parent) {
+                Ok(entries) => entries
+                    .filter_map(|entry| {
+                        let entry = entry.ok()?;
+                        let metadata = entry.metadata().ok()?;
+                        metadata.is_file().then(|| {
+                            (
+                                entry.file_name().to_string_lossy().into_owned(),
+                                metadata.len(),
+                            )
+                        })
+                    })
+                    .collect(),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+                Err(err) => return Err(SyncError::local(parent, &err)),
+            },
+            VersionStorage::Remote => {
+                match rclone::sync::list(&self.remote_name, parent, false, RcloneListFilter::Files)
+                {
+                    Ok(items) => items
+                        .into_iter()
+                        .map(|item| (item.name, item.size.max(0) as u64))
+                        .collect(),
+                    Err(err) if err.kind() == RcloneErrorKind::NotFound => vec![],
+                    Err(err) => return Err(SyncError::rclone(parent, err)),
+                }
+            }
+        };
+
+        let mut versions: Vec<((i64, u32), FileVersion)> = files
+            .into_iter()
+            .filter_map(|(name, size)| {
+                let (timestamp, number) = parse_suffix(name.strip_prefix(&prefix)?)?;
+                Some((
+                    (timestamp, number),
+                    FileVersion {
+                        timestamp,
+                        size,
+                        storage,
+                        path: format!("{parent}/{name}"),
+                    },
+                ))
+            })
+            .collect();
+        versions.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        Ok(versions.into_iter().map(|(_, version)| version).collect())
+    }
+
+    /// Replace the local file at `local_path` with a previous version of it.
+    /// The next sync then pushes it to the remote like any other local change,
+    /// which keeps the content being replaced there as a version of its own.
+    pub fn restore(&self, version: &FileVersion, local_path: &str) -> Result<(), SyncError> {
+        match version.storage {
+            VersionStorage::Off => Ok(()),
+            VersionStorage::Local => fs::copy(&version.path, local_path)
+                .map(|_| ())
+                .map_err(|err| SyncError::local(local_path, &err)),
+            VersionStorage::Remote => {
+                rclone::sync::download_file(local_path, &self.remote_name, &version.path)
+                    .map_err(|err| SyncError::rclone(&version.path, err))
+            }
+        }
+    }
+
+    /// Remove all versions older than the sync directory's retention policy
+    /// allows. The number of versions per file is already enforced whenever a
+    /// new version is kept. As this has to go through the whole versions
+    /// directory, it only runs once a day per sync directory.
+    pub fn prune(&self) -> Result<(), SyncError> {
+        if self.sync_dir.max_version_days == 0 {
+            return Ok(());
+        }
+
+        {
+            let mut last_pruned = LAST_PRUNED.lock().unwrap();
+            let now = now();
+
+            if let Some(time) = last_pruned.get(&self.sync_dir.id)
+                && now - time < DAY
+            {
+                return Ok(());
+            }
+            last_pruned.insert(self.sync_dir.id, now);
+        }
+
+        match self.sync_dir.version_storage {
+            VersionStorage::Off => Ok(()),
+            VersionStorage::Local => self.prune_local_dir(Path::new(&self.local_root())),
+            VersionStorage::Remote => {
+                let root = self.remote_root();
+                let items = match rclone::sync::list(
+                    &self.remote_name,
+                    &root,
+                    true,
+                    RcloneListFilter::Files,
+                ) {
+                    Ok(items) => items,
+                    Err(err) if err.kind() == RcloneErrorKind::NotFound => return Ok(()),
+                    Err(err) => return Err(SyncError::rclone(&root, err)),
+                };
+
+                for item in items {
+                    if self.expired(&item.name) {
+                        rclone::sync::delete(&self.remote_name, &item.path)
+                            .map_err(|err| SyncError::rclone(&item.path, err))?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove the versions older than the retention policy allows from a local
+    /// directory, and everything below it.
+    fn prune_local_dir(&self, dir: &Path) -> Result<(), SyncError> {
+        let dir_string = dir.to_string_lossy().into_owned();
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(SyncError::local(&dir_string, &err)),
+        };
+
+        for entry in entries {
+            let entry = entry.map_err(|err| SyncError::local(&dir_string, &err))?;
+            let path = entry.path();
+
+            if path.is_dir() {
+                self.prune_local_dir(&path)?;
+            } else if self.expired(&entry.file_name().to_string_lossy()) {
+                fs::remove_file(&path)
+                    .map_err(|err| SyncError::local(&path.to_string_lossy(), &err))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove the versions of the file at `local_path` that go beyond the
+    /// retention policy.
+    fn prune_file(&self, local_path: &str) -> Result<(), SyncError> {
+        let max_versions = self.sync_dir.max_versions as usize;
+
+        for (index, version) in self.list(local_path)?.iter().enumerate() {
+            let expired = self.sync_dir.max_version_days != 0
+                && version.timestamp < now() - self.sync_dir.max_version_days as i64 * DAY;
+
+            if !expired && (max_versions == 0 || index < max_versions) {
+                continue;
+            }
+
+            match version.storage {
+                VersionStorage::Off => (),
+                VersionStorage::Local => fs::remove_file(&version.path)
+                    .map_err(|err| SyncError::local(&version.path, &err))?,
+                VersionStorage::Remote => rclone::sync::delete(&self.remote_name, &version.path)
+                    .map_err(|err| SyncError::rclone(&version.path, err))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check if the version with the given file name is older than the
+    /// retention policy allows.
+    fn expired(&self, file_name: &str) -> bool {
+        let Some((timestamp, _)) = file_name
+            .rsplit_once('.')
+            .and_then(|(_, suffix)| parse_suffix(suffix))
+        else {
+            return false;
+        };
+
+        timestamp < now() - self.sync_dir.max_version_days as i64 * DAY
+    }
+
+    /// Get the path of the local versions directory.
+    fn local_root(&self) -> String {
+        format!("{}/{VERSIONS_DIR_NAME}", self.sync_dir.local_path)
+    }
+
+    /// Get the path of the remote versions directory.
+    fn remote_root(&self) -> String {
+        if self.sync_dir.remote_path.is_empty() {
+            VERSIONS_DIR_NAME.to_owned()
+        } else {
+            format!("{}/{VERSIONS_DIR_NAME}", self.sync_dir.remote_path)
+        }
+    }
+
+    /// Get the path to keep the version of the file at `local_path` from
+    /// `timestamp` at, in the sync directory's current storage. A number gets
+    /// appended if a version from the same second already exists.
+    fn version_path(&self, local_path: &str, timestamp: i64) -> Result<String, SyncError> {
+        let base_path = format!("{}.{timestamp}", self.base_path(local_path));
+        let mut path = base_path.clone();
+        let mut number = 1;
+
+        while self.exists(&path)? {
+            number += 1;
+            path = format!("{base_path}-{number}");
+        }
+
+        Ok(path)
+    }
+
+    /// Check if a version exists at `path` in the sync directory's current
+    /// storage.
+    fn exists(&self, path: &str) -> Result<bool, SyncError> {
+        match self.sync_dir.version_storage {
+            VersionStorage::Off => Ok(false),
+            VersionStorage::Local => Ok(Path::new(path).exists()),
+            VersionStorage::Remote => rclone::sync::stat(&self.remote_name, path)
+                .map(|item| item.is_some())
+                .map_err(|err| SyncError::rclone(path, err)),
+        }
+    }
+
+    /// Get the path of the file at `local_path` in the sync directory's current
+    /// storage, which its versions get a timestamp appended to.
+    fn base_path(&self, local_path: &str) -> String {
+        let relative_path = local_path
+            .strip_prefix(&format!("{}/", self.sync_dir.local_path))
+            .unwrap();
+        let root = match self.sync_dir.version_storage {
+            VersionStorage::Remote => self.remote_root(),
+            VersionStorage::Off | VersionStorage::Local => self.local_root(),
+        };
+
+        format!("{root}/{relative_path}")
+    }
+}
+
+/// Parse the suffix of a version's file name into the timestamp it was kept at
+/// and its number within that second.
+fn parse_suffix(suffix: &str) -> Option<(i64, u32)> {
+    match suffix.split_once('-') {
+        Some((timestamp, number)) => Some((timestamp.parse().ok()?, number.parse().ok()?)),
+        None => Some((suffix.parse().ok()?, 1)),
+    }
+}
+
+/// Get the current UNIX timestamp.
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
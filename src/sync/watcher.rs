@@ -0,0 +1,272 @@
+//! Local change detection through inotify.
+//!
+//! Walking a whole sync directory to find out that nothing changed gets
+//! expensive on large trees, so a [`LocalWatcher`] keeps track of the paths
+//! that were touched since the last sync instead. A full rescan is still
+//! requested every so often, and whenever inotify drops events, so that
+//! nothing slips through.
+use nix::{
+    errno::Errno,
+    sys::inotify::{AddWatchFlags, InitFlags, Inotify, WatchDescriptor},
+    unistd,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// How often a full rescan of a sync directory is done, regardless of any
+/// events we received.
+const FULL_RESCAN_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// The local changes found in a sync directory since its last sync.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LocalChanges {
+    /// The whole directory needs to be checked.
+    Full,
+    /// Only the given paths need to be checked. An empty set means nothing
+    /// changed locally.
+    Paths(HashSet<PathBuf>),
+}
+
+/// Watches the local path of a sync directory for changes.
+pub struct LocalWatcher {
+    inotify: Inotify,
+    local_path: PathBuf,
+    /// The directory each watch was added for.
+    watches: HashMap<WatchDescriptor, PathBuf>,
+    /// The paths that changed since the last sync, along with the generation
+    /// they last changed in.
+    dirty_paths: HashMap<PathBuf, u64>,
+    /// The generation new changes get recorded in. It goes up every time
+    /// [`Self::changes`] is called, so that [`Self::clear`] can tell the
+    /// changes it was given apart from ones that came in while syncing.
+    generation: u64,
+    /// The generation we lost track of some changes in, such as from the event
+    /// queue overflowing, if we need a full rescan.
+    rescan_generation: Option<u64>,
+    last_full_scan: Instant,
+}
+
+impl LocalWatcher {
+    /// Start watching `local_path` and all the directories below it.
+    pub fn new(local_path: &str) -> nix::Result<Self> {
+        let inotify = Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)?;
+        let mut watcher = Self {
+            inotify,
+            local_path: PathBuf::from(local_path),
+            watches: HashMap::new(),
+            dirty_paths: HashMap::new(),
+            generation: 0,
+            // We don't know what changed before we started watching.
+            rescan_generation: Some(0),
+            last_full_scan: Instant::now(),
+        };
+
+        // Make sure the root itself can be watched, as it's the one thing we
+        // can't fall back to a rescan for.
+        let wd = watcher
+            .inotify
+            .add_watch(&watcher.local_path, Self::watch_flags())?;
+        let local_path = watcher.local_path.clone();
+        watcher.watches.insert(wd, local_path.clone());
+        watcher.watch_children(&local_path);
+
+        Ok(watcher)
+    }
+
+    /// Get the changes found since the last call to [`Self::clear`]. Changes
+    /// that come in after this call are kept around by the next call to
+    /// [`Self::clear`], so they get synced later on.
+    pub fn changes(&mut self) -> LocalChanges {
+        self.read_events();
+        self.generation += 1;
+
+        if self.rescan_generation.is_some() || self.last_full_scan.elapsed() >= FULL_RESCAN_INTERVAL
+        {
+            LocalChanges::Full
+        } else {
+            LocalChanges::Paths(self.dirty_paths.keys().cloned().collect())
+        }
+    }
+
+    /// Forget about the given changes from the last call to [`Self::changes`],
+    /// after they've been synced.
+    pub fn clear(&mut self, changes: &LocalChanges) {
+        // The generation the changes were taken in.
+        let generation = self.generation.saturating_sub(1);
+
+        match changes {
+            LocalChanges::Full => {
+                self.dirty_paths
+                    .retain(|_, path_generation| *path_generation > generation);
+                if self
+                    .rescan_generation
+                    .is_some_and(|rescan_generation| rescan_generation <= generation)
+                {
+                    self.rescan_generation = None;
+                }
+                self.last_full_scan = Instant::now();
+            }
+            LocalChanges::Paths(paths) => self.dirty_paths.retain(|path, path_generation| {
+                *path_generation > generation || !paths.contains(path)
+            }),
+        }
+    }
+
+    /// Forget about the changed paths `is_changed` returns `false` for, such as
+    /// the files a sync just wrote itself.
+    pub fn forget_unchanged(&mut self, is_changed: impl Fn(&Path) -> bool) {
+        self.read_events();
+        self.dirty_paths.retain(|path, _| is_changed(path));
+    }
+
+    /// Whether the sync directory itself is still being watched. If it isn't,
+    /// it was moved or deleted, and a new watcher needs to be created once it's
+    /// back.
+    pub fn is_watching(&self) -> bool {
+        self.watches.values().any(|path| path == &self.local_path)
+    }
+
+    /// The events we want to know about for every watched directory.
+    fn watch_flags() -> AddWatchFlags {
+        AddWatchFlags::IN_CREATE
+            | AddWatchFlags::IN_CLOSE_WRITE
+            | AddWatchFlags::IN_ATTRIB
+            | AddWatchFlags::IN_DELETE
+            | AddWatchFlags::IN_MOVED_FROM
+            | AddWatchFlags::IN_MOVED_TO
+            | AddWatchFlags::IN_DELETE_SELF
+            | AddWatchFlags::IN_MOVE_SELF
+            | AddWatchFlags::IN_ONLYDIR
+    }
+
+    /// Add a watch for a directory and everything below it, marking each path
+    /// in it as dirty.
+    fn watch_dir(&mut self, dir: &Path) {
+        match self.inotify.add_watch(dir, Self::watch_flags()) {
+            Ok(wd) => {
+                self.watches.insert(wd, dir.to_owned());
+            }
+            // The directory could have been removed again already, in which case
+            // the deletion event will show up on its parent.
+            Err(Errno::ENOENT) | Err(Errno::ENOTDIR) => return,
+            // Most likely we ran out of watches, so fall back to rescanning.
+            Err(_) => {
+                self.rescan_generation = Some(self.generation);
+                return;
+            }
+        }
+
+        self.watch_children(dir);
+    }
+
+    /// Add watches for the subdirectories of a directory, marking each path in
+    /// it as dirty.
+    fn watch_children(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            self.dirty_paths.insert(path.clone(), self.generation);
+
+            if entry
+                .file_type()
+                .map(|file_type| file_type.is_dir())
+                .unwrap_or(false)
+            {
+                self.watch_dir(&path);
+            }
+        }
+    }
+
+    /// Remove the watches for a directory and everything below it.
+    fn unwatch_dir(&mut self, dir: &Path) {
+        let removed: Vec<WatchDescriptor> = self
+            .watches
+            .iter()
+            .filter(|(_, path)| path.starts_with(dir))
+            .map(|(wd, _)| *wd)
+            .collect();
+
+        for wd in removed {
+            self.watches.remove(&wd);
+            // This fails if the kernel already dropped the watch, which is fine.
+            let _ = self.inotify.rm_watch(wd);
+        }
+    }
+
+    /// Read all pending events from inotify and record the paths they were for.
+    fn read_events(&mut self) {
+        loop {
+            let events = match self.inotify.read_events() {
+                Ok(events) => events,
+                Err(Errno::EAGAIN) => return,
+                Err(_) => {
+                    self.rescan_generation = Some(self.generation);
+                    return;
+                }
+            };
+
+            for event in events {
+                if event.mask.contains(AddWatchFlags::IN_Q_OVERFLOW) {
+                    self.rescan_generation = Some(self.generation);
+                    continue;
+                }
+
+                let Some(dir) = self.watches.get(&event.wd).cloned() else {
+                    continue;
+                };
+
+                // The kernel removed the watch, i.e. because the directory got
+                // deleted.
+                if event.mask.contains(AddWatchFlags::IN_IGNORED) {
+                    self.watches.remove(&event.wd);
+                    continue;
+                }
+
+                // The sync directory itself got moved or deleted. There's nothing left to
+                // watch, so drop everything and let the rescan find out what happened.
+                if dir == self.local_path
+                    && event
+                        .mask
+                        .intersects(AddWatchFlags::IN_DELETE_SELF | AddWatchFlags::IN_MOVE_SELF)
+                {
+                    self.unwatch_dir(&dir);
+                    self.rescan_generation = Some(self.generation);
+                    continue;
+                }
+
+                let Some(name) = event.name else {
+                    continue;
+                };
+                let path = dir.join(name);
+
+                if event.mask.contains(AddWatchFlags::IN_ISDIR) {
+                    if event.mask.contains(AddWatchFlags::IN_MOVED_FROM) {
+                        self.unwatch_dir(&path);
+                    } else if event
+                        .mask
+                        .intersects(AddWatchFlags::IN_CREATE | AddWatchFlags::IN_MOVED_TO)
+                    {
+                        self.watch_dir(&path);
+                    }
+                }
+
+                self.dirty_paths.insert(path, self.generation);
+            }
+        }
+    }
+}
+
+impl Drop for LocalWatcher {
+    fn drop(&mut self) {
+        // `Inotify` doesn't close its file descriptor by itself.
+        let _ = unistd::close(self.inotify.as_raw_fd());
+    }
+}
@@ -1,9 +1,23 @@
-use crate::{launch, util};
-use ksni::{menu::StandardItem, MenuItem, Tray as KsniTray};
+use crate::{bwlimit, launch, util};
+use ksni::{
+    menu::{StandardItem, SubMenu},
+    MenuItem, Tray as KsniTray,
+};
 
 pub struct Tray {
     status: String,
     pub icon: String,
+    /// The bandwidth limit currently in effect.
+    bandwidth_limit: String,
+    /// Whether syncing is paused.
+    paused: bool,
+    /// The storage usage of each remote that reports it, as a remote name and
+    /// usage summary.
+    quotas: Vec<(String, String)>,
+    /// A description of each running file transfer.
+    transfers: Vec<String>,
+    /// A description of each item synced most recently, newest first.
+    recent: Vec<String>,
 }
 
 impl Tray {
@@ -12,6 +26,11 @@ impl Tray {
         Self {
             status: tr::tr!("Awaiting sync checks..."),
             icon: "com.hunterwittenborn.Celeste.CelesteTrayLoading-symbolic".to_owned(),
+            bandwidth_limit: bwlimit::UNLIMITED.to_owned(),
+            paused: false,
+            quotas: vec![],
+            transfers: vec![],
+            recent: vec![],
         }
     }
 
@@ -19,6 +38,30 @@ impl Tray {
         self.status = msg.to_string();
     }
 
+    pub fn set_bandwidth_limit(&mut self, rate: &str) {
+        self.bandwidth_limit = rate.to_owned();
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+
+        if paused {
+            self.icon = "com.hunterwittenborn.Celeste.CelesteTrayPaused-symbolic".to_owned();
+        }
+    }
+
+    pub fn set_quotas(&mut self, quotas: Vec<(String, String)>) {
+        self.quotas = quotas;
+    }
+
+    pub fn set_transfers(&mut self, transfers: Vec<String>) {
+        self.transfers = transfers;
+    }
+
+    pub fn set_recent(&mut self, recent: Vec<String>) {
+        self.recent = recent;
+    }
+
     pub fn set_syncing(&mut self) {
         self.icon = "com.hunterwittenborn.Celeste.CelesteTraySyncing-symbolic".to_owned();
     }
@@ -31,6 +74,10 @@ impl Tray {
         self.icon = "com.hunterwittenborn.Celeste.CelesteTrayDone-symbolic".to_owned();
     }
 
+    pub fn set_root_missing(&mut self) {
+        self.icon = "com.hunterwittenborn.Celeste.CelesteTrayWarning-symbolic".to_owned();
+    }
+
     pub fn set_disconnected(&mut self) {
         self.icon = "com.hunterwittenborn.Celeste.CelesteTrayDisconnected-symbolic".to_owned();
     }
@@ -50,11 +97,76 @@ impl KsniTray for Tray {
     }
 
     fn menu(&self) -> Vec<MenuItem<Self>> {
-        vec![
-            MenuItem::Standard(StandardItem {
-                label: self.status.clone(),
+        let bandwidth_label = if self.bandwidth_limit == bwlimit::UNLIMITED {
+            tr::tr!("Bandwidth limit: None")
+        } else {
+            tr::tr!("Bandwidth limit: {}", self.bandwidth_limit)
+        };
+
+        let mut items = vec![MenuItem::Standard(StandardItem {
+            label: self.status.clone(),
+            enabled: false,
+            ..Default::default()
+        })];
+
+        for transfer in &self.transfers {
+            items.push(MenuItem::Standard(StandardItem {
+                label: transfer.clone(),
                 enabled: false,
                 ..Default::default()
+            }));
+        }
+
+        items.push(MenuItem::Standard(StandardItem {
+            label: bandwidth_label,
+            enabled: false,
+            ..Default::default()
+        }));
+
+        for (remote_name, summary) in &self.quotas {
+            items.push(MenuItem::Standard(StandardItem {
+                label: tr::tr!("{}: {}", remote_name, summary),
+                enabled: false,
+                ..Default::default()
+            }));
+        }
+
+        if !self.recent.is_empty() {
+            items.push(MenuItem::SubMenu(SubMenu {
+                label: tr::tr!("Recently Synced"),
+                submenu: self
+                    .recent
+                    .iter()
+                    .map(|item| {
+                        MenuItem::Standard(StandardItem {
+                            label: item.clone(),
+                            enabled: false,
+                            ..Default::default()
+                        })
+                    })
+                    .collect(),
+                ..Default::default()
+            }));
+        }
+
+        items.extend([
+            MenuItem::Standard(StandardItem {
+                label: if self.paused {
+                    tr::tr!("Resume Syncing")
+                } else {
+                    tr::tr!("Pause Syncing")
+                },
+                activate: Box::new(|tray: &mut Self| {
+                    *(*launch::PAUSE_REQUEST).lock().unwrap() = Some(!tray.paused);
+                }),
+                ..Default::default()
+            }),
+            MenuItem::Standard(StandardItem {
+                label: tr::tr!("Check for Changes Now"),
+                activate: Box::new(|_| {
+                    *(*launch::CHECK_REQUEST).lock().unwrap() = true;
+                }),
+                ..Default::default()
             }),
             MenuItem::Standard(StandardItem {
                 label: tr::tr!("Open"),
@@ -70,6 +182,8 @@ impl KsniTray for Tray {
                 }),
                 ..Default::default()
             }),
-        ]
+        ]);
+
+        items
     }
 }
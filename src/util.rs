@@ -15,6 +15,14 @@ pub fn run_in_background<T: Send + 'static, F: FnOnce() -> T + Send + 'static>(f
     MainContext::default().block_on(blocking::unblock(f))
 }
 
+/// Get the current time in the local time zone, or in UTC if the local time
+/// zone can't be found.
+pub fn now_local() -> glib::DateTime {
+    glib::DateTime::now_local()
+        .or_else(|_| glib::DateTime::now_utc())
+        .unwrap()
+}
+
 /// Format a directory with the user's home directory replaced with '~'.
 pub fn fmt_home(dir: &str) -> String {
     let home_dir = glib::home_dir().into_os_string().into_string().unwrap();
@@ -32,6 +40,14 @@ pub fn get_config_dir() -> PathBuf {
     config_dir
 }
 
+/// Get the user's trash directory, as described by the FreeDesktop trash
+/// specification.
+pub fn get_trash_dir() -> PathBuf {
+    let mut trash_dir = glib::user_data_dir();
+    trash_dir.push("Trash");
+    trash_dir
+}
+
 /// Strip the slashes from the beginning and end of a string.
 pub fn strip_slashes(string: &str) -> String {
     let stripped_prefix = match string.strip_prefix('/') {